@@ -0,0 +1,314 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generates `implicants.h` from `src/lib.rs` into `OUT_DIR`, and gives the
+//! shared library a versioned soname.  Installing, including the
+//! pkg-config file, is up to the `Makefile`.
+//!
+//! The header generator only understands the handful of constructs that
+//! `src/lib.rs` actually uses; it panics on anything else.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LICENSE: &str = "\
+/* implicants – Enumerate (prime) implicants of an arbitrary function
+ * Copyright (C) 2017  Ben Wiederhake
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+";
+
+const PROLOGUE: &str = "
+/* Generated from src/lib.rs by build.rs.  Do not edit. */
+
+#ifndef IMPLICANTS_C_HEADER
+#define IMPLICANTS_C_HEADER
+
+#if defined(__cplusplus) && __cplusplus > 199711L
+#include <cstddef>
+#include <cstdint>
+#else
+#include <stddef.h>
+#include <stdint.h>
+#endif
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+";
+
+const EPILOGUE: &str = "
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* IMPLICANTS_C_HEADER */
+";
+
+/// Rust name, C name, and for opaque structs the C struct tag.
+const NAMES: &[(&str, &str, &str)] = &[
+    ("SampleFnC", "sample_fn_t", ""),
+    ("ReportFnC", "report_fn_t", ""),
+    ("SampleBlockFnC", "sample_block_fn_t", ""),
+    ("Status", "implicants_status_t", ""),
+    ("CollectedResult", "implicants_result_t", "implicants_result"),
+];
+
+/// The C name of a type from `NAMES`.
+fn c_name(rust: &str) -> &'static str {
+    match NAMES.iter().find(|entry| entry.0 == rust) {
+        Some(entry) => entry.1,
+        None => panic!("Don't know the C name of '{}'", rust),
+    }
+}
+
+fn c_type(rust: &str) -> String {
+    let rust = rust.trim();
+    if let Some(inner) = rust.strip_prefix("*mut ") {
+        return format!("{}*", c_type(inner));
+    }
+    if let Some(inner) = rust.strip_prefix("*const ") {
+        return format!("const {}*", c_type(inner));
+    }
+    if let Some(inner) = rust.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        // Only for function pointers, which are nullable in C anyway.
+        return c_type(inner);
+    }
+    match rust {
+        "c_int" => "int",
+        "c_char" => "char",
+        "c_void" => "void",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "size_t" => "size_t",
+        other => c_name(other),
+    }
+    .to_string()
+}
+
+/// `"a: A, b: B"` to `"A' a, B' b"`, or `"void"`.
+fn c_params(rust: &str) -> Vec<String> {
+    let params: Vec<String> = rust
+        .split(',')
+        .map(str::trim)
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (name, ty) = param.split_at(param.find(':').expect("Parameter without type"));
+            format!("{} {}", c_type(&ty[1..]), name.trim())
+        })
+        .collect();
+    if params.is_empty() {
+        vec!["void".to_string()]
+    } else {
+        params
+    }
+}
+
+/// Splits `"name(params) -> ret"` into its parts.
+fn split_signature(sig: &str) -> (&str, &str, String) {
+    let open = sig.find('(').expect("Missing parameter list");
+    let close = sig.rfind(')').expect("Missing parameter list");
+    let ret = match sig[close + 1..].trim().strip_prefix("->") {
+        Some(ret) => c_type(ret),
+        None => "void".to_string(),
+    };
+    (sig[..open].trim(), &sig[open + 1..close], ret)
+}
+
+fn c_comment(docs: &[String], indent: &str) -> String {
+    let mut lines: Vec<String> = docs
+        .iter()
+        .take_while(|line| line.as_str() != "# Safety")
+        .map(|line| line.replace('`', ""))
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+    let mut comment = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        comment.push_str(indent);
+        comment.push_str(if i == 0 { "/* " } else { " * " });
+        comment.push_str(line);
+        comment.push_str(if i + 1 == lines.len() { " */\n" } else { "\n" });
+    }
+    comment
+}
+
+/// `InvalidArgument` to `INVALID_ARGUMENT`.
+fn screaming(camel: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in camel.chars().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            result.push('_');
+        }
+        result.push(c.to_ascii_uppercase());
+    }
+    result
+}
+
+fn declaration(head: &str, params: &[String]) -> String {
+    let line = format!("{}({});\n", head, params.join(", "));
+    if line.len() <= 80 {
+        return line;
+    }
+    let mut result = format!("{}(\n   ", head);
+    let mut width = 3;
+    for (i, param) in params.iter().enumerate() {
+        let piece = if i + 1 == params.len() {
+            format!(" {});\n", param)
+        } else {
+            format!(" {},", param)
+        };
+        if width + piece.len() > 78 && width > 3 {
+            result.push_str("\n   ");
+            width = 3;
+        }
+        width += piece.len();
+        result.push_str(&piece);
+    }
+    result
+}
+
+fn generate_header(source: &str) -> String {
+    let mut header = String::new();
+    header.push_str(LICENSE);
+    header.push_str(PROLOGUE);
+
+    let mut docs: Vec<String> = Vec::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        if let Some(doc) = line.strip_prefix("///") {
+            docs.push(doc.strip_prefix(' ').unwrap_or(doc).to_string());
+            continue;
+        }
+        if line == "#[cfg(test)]" || line == "#[test]" {
+            // Skip the entire item.
+            for skipped in lines.by_ref() {
+                if skipped == "}" || (!skipped.starts_with(' ') && skipped.ends_with(';')) {
+                    break;
+                }
+            }
+        } else if line.starts_with("#[") || line.starts_with("//") {
+            // Attributes and plain comments don't separate docs from their item.
+            continue;
+        } else if let Some(rest) = line.strip_prefix("type ") {
+            let eq = rest.find('=').expect("Type alias without '='");
+            let fn_type = rest[eq + 1..].trim().trim_end_matches(';');
+            let fn_sig = fn_type.strip_prefix("extern \"C\" fn").expect("Not a C function pointer");
+            let (_, params, ret) = split_signature(fn_sig);
+            header.push('\n');
+            header.push_str(&c_comment(&docs, ""));
+            header.push_str(&format!("typedef {} (*{})({});\n",
+                                     ret,
+                                     c_name(rest[..eq].trim()),
+                                     c_params(params).join(", ")));
+        } else if let Some(rest) = line.strip_prefix("pub enum ") {
+            let name = rest.trim_end_matches('{').trim();
+            header.push('\n');
+            header.push_str(&c_comment(&docs, ""));
+            header.push_str("typedef enum {\n");
+            let mut variants = Vec::new();
+            let mut variant_docs = Vec::new();
+            for variant in lines.by_ref() {
+                let variant = variant.trim();
+                if variant == "}" {
+                    break;
+                } else if let Some(doc) = variant.strip_prefix("///") {
+                    variant_docs.push(doc.trim().to_string());
+                } else {
+                    let (ident, value) = variant.trim_end_matches(',').split_at(variant.find('=').expect("Variant without explicit value"));
+                    variants.push(format!("{}    IMPLICANTS_{} = {}",
+                                          c_comment(&variant_docs, "    "),
+                                          screaming(ident.trim()),
+                                          value[1..].trim()));
+                    variant_docs.clear();
+                }
+            }
+            header.push_str(&variants.join(",\n"));
+            header.push_str(&format!("\n}} {};\n", c_name(name)));
+        } else if let Some(rest) = line.strip_prefix("pub struct ") {
+            let name = rest.trim_end_matches('{').trim();
+            let entry = NAMES.iter().find(|entry| entry.0 == name).expect("Unknown struct");
+            assert!(!entry.2.is_empty(), "Only opaque structs are supported");
+            if !line.ends_with(';') {
+                for skipped in lines.by_ref() {
+                    if skipped == "}" {
+                        break;
+                    }
+                }
+            }
+            header.push('\n');
+            header.push_str(&c_comment(&docs, ""));
+            header.push_str(&format!("typedef struct {} {};\n", entry.2, entry.1));
+        } else if line.starts_with("pub extern \"C\" fn ") || line.starts_with("pub unsafe extern \"C\" fn ") {
+            let mut sig = line[line.find(" fn ").unwrap() + 4..].to_string();
+            while !sig.trim_end().ends_with('{') {
+                sig.push(' ');
+                sig.push_str(lines.next().expect("Unterminated signature").trim());
+            }
+            let sig = sig.trim_end().trim_end_matches('{');
+            let (name, params, ret) = split_signature(sig);
+            header.push('\n');
+            header.push_str(&c_comment(&docs, ""));
+            header.push_str(&declaration(&format!("{} {}", ret, name), &c_params(params)));
+        }
+        docs.clear();
+    }
+
+    header.push_str(EPILOGUE);
+    header
+}
+
+fn write_if_changed(path: &Path, contents: &str) {
+    if fs::read_to_string(path).ok().as_deref() != Some(contents) {
+        fs::write(path, contents).unwrap_or_else(|e| panic!("Cannot write {}: {}", path.display(), e));
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/lib.rs");
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    let source = fs::read_to_string(root.join("src").join("lib.rs")).unwrap();
+    let header = generate_header(&source);
+    // The checked-in copy in include/ is compared against this one by a
+    // test, but never written from here: the source tree may be read-only.
+    write_if_changed(&out_dir.join("implicants.h"), &header);
+
+    if env::var("CARGO_CFG_TARGET_OS").unwrap() == "linux" {
+        let soname = format!("libimplicants.so.{}", env::var("CARGO_PKG_VERSION_MAJOR").unwrap());
+        println!("cargo:rustc-cdylib-link-arg=-Wl,-soname,{}", soname);
+    }
+}
@@ -0,0 +1,55 @@
+/* implicants – Enumerate (prime) implicants of an arbitrary function
+ * Copyright (C) 2017  Ben Wiederhake
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+/* Compile with:
+
+gcc -o collect collect.c -I../include/ -L../../target/debug/ \
+    -limplicants -lutil -ldl -lrt -lpthread -lgcc_s -lc -lm -lrt -lutil
+
+Like print.c, this is also valid C++ code.
+
+*/
+
+#include <implicants.h>
+#include <stdio.h>
+
+static int my_fn(void* __base, uint32_t v) {
+    (void)sizeof(__base);  /* Ignore argument */
+    return 1 & (v >> (1 + (v & 1)));
+}
+
+int main(int argc, char** argv) {
+    implicants_result_t* result;
+    size_t i;
+    (void)sizeof(argc);  /* Ignore argument */
+    (void)sizeof(argv);  /* Ignore argument */
+
+    if (implicants_collect(my_fn, NULL, 3, 1, &result) != IMPLICANTS_OK) {
+        printf("Failed: %s\n", implicants_last_error_message());
+        return 1;
+    }
+
+    printf("Found %lu prime implicants:\n", (unsigned long)implicants_result_len(result));
+    for (i = 0; i < implicants_result_len(result); ++i) {
+        uint32_t mask_gap, value;
+        implicants_result_get(result, i, &mask_gap, &value, NULL);
+        printf("%08x/%08x\n", mask_gap, value);
+    }
+
+    implicants_result_free(result);
+    return 0;
+}
@@ -17,24 +17,30 @@
 
 /* Compile with:
 
-gcc -o print print.c -I../include/ -L../target/debug/ \
+gcc -o print print.c -I../include/ -L../../target/debug/ \
     -limplicants -lutil -ldl -lrt -lpthread -lgcc_s -lc -lm -lrt -lutil
 
 Or more pedantically:
 
-gcc -o print print.c -I../include/ -L../target/debug/ \
+gcc -o print print.c -I../include/ -L../../target/debug/ \
     -limplicants -lutil -ldl -lrt -lpthread -lgcc_s -lc -lm -lrt -lutil \
     -Wall -Wextra -pedantic -std=c99
 
+Or against the shared library, using the pkg-config file that running
+'make' in the top directory generates:
+
+gcc -o print print.c \
+    $(PKG_CONFIG_PATH=../../target/release/ pkg-config --cflags --libs implicants)
+
 This is also valid C++ code!  The header file properly includes C++
 headers when it can.  Copy this file to 'print.cpp', and compile as:
 
-g++ -o print print.cpp -I../include/ -L../target/debug/ \
+g++ -o print print.cpp -I../include/ -L../../target/debug/ \
     -limplicants -lutil -ldl -lrt -lpthread -lgcc_s -lc -lm -lrt -lutil
 
 Or more pedantically:
 
-g++ -o print print.cpp -I../include/ -L../target/debug/ \
+g++ -o print print.cpp -I../include/ -L../../target/debug/ \
     -limplicants -lutil -ldl -lrt -lpthread -lgcc_s -lc -lm -lrt -lutil \
     -Wall -Wextra -pedantic -std=c++98
 
@@ -60,7 +66,10 @@ int main(int argc, char** argv) {
     (void)sizeof(argv);  /* Ignore argument */
 
     printf("Hello world!\n");
-    implicants_generate(my_fn, NULL, print_it, NULL, 3);
+    if (implicants_generate(my_fn, NULL, print_it, NULL, 3) != IMPLICANTS_OK) {
+        printf("Failed: %s\n", implicants_last_error_message());
+        return 1;
+    }
     printf("That's all.\n");
 
     return 0;
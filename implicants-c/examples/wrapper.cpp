@@ -0,0 +1,63 @@
+/* implicants – Enumerate (prime) implicants of an arbitrary function
+ * Copyright (C) 2017  Ben Wiederhake
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+/* Compile with:
+
+g++ -o wrapper wrapper.cpp -I../include/ -L../../target/debug/ \
+    -limplicants -lutil -ldl -lrt -lpthread -lgcc_s -lc -lm -lrt -lutil \
+    -Wall -Wextra -pedantic -std=c++11
+
+This is also what tests/cpp.rs does.
+
+*/
+
+#include <implicants.hpp>
+
+#include <cstdio>
+#include <stdexcept>
+
+int main() {
+    const uint32_t divisor = 3;
+    auto my_fn = [divisor](uint32_t v) { return v % divisor == 0; };
+
+    unsigned count = 0;
+    implicants::generate(my_fn, [&count](uint32_t, uint32_t, bool) { ++count; }, 3);
+    std::printf("%u implicants\n", count);
+
+    for (const implicants::cube& c : implicants::collect(my_fn, 3, true)) {
+        std::printf("%08x/%08x\n", c.mask_gap, c.value);
+    }
+
+    try {
+        implicants::collect(my_fn, 32);
+    } catch (const implicants::error& e) {
+        std::printf("error %d: %s\n", static_cast<int>(e.status()), e.what());
+    }
+
+    try {
+        implicants::collect([](uint32_t v) -> bool {
+            if (v == 5) {
+                throw std::runtime_error("no fives");
+            }
+            return true;
+        }, 3);
+    } catch (const std::runtime_error& e) {
+        std::printf("sampling failed: %s\n", e.what());
+    }
+
+    return 0;
+}
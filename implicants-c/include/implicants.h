@@ -0,0 +1,96 @@
+/* implicants – Enumerate (prime) implicants of an arbitrary function
+ * Copyright (C) 2017  Ben Wiederhake
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+/* Generated from src/lib.rs by build.rs.  Do not edit. */
+
+#ifndef IMPLICANTS_C_HEADER
+#define IMPLICANTS_C_HEADER
+
+#if defined(__cplusplus) && __cplusplus > 199711L
+#include <cstddef>
+#include <cstdint>
+#else
+#include <stddef.h>
+#include <stdint.h>
+#endif
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef int (*sample_fn_t)(void* base, uint32_t v);
+
+typedef void (*report_fn_t)(void* base, uint32_t m, uint32_t nonm, int is_prime);
+
+typedef void (*sample_block_fn_t)(void* base, uint32_t start, uint64_t* out, size_t len);
+
+typedef enum {
+    IMPLICANTS_OK = 0,
+    /* E.g. a NULL callback, or an arity above 31. */
+    IMPLICANTS_INVALID_ARGUMENT = 1,
+    /* Internal error.  Please report a bug. */
+    IMPLICANTS_PANIC = 2
+} implicants_status_t;
+
+/* Callbacks must not unwind (C++ exceptions, longjmp) into the library. */
+implicants_status_t implicants_generate(
+    sample_fn_t sample, void* sample_base, report_fn_t report,
+    void* report_base, uint32_t arity);
+
+/* Like implicants_generate, but sample fills len words at once: bit j
+ * of out[i] is the value at input start + 64*i + j.  The words are zero
+ * on entry, and bits for inputs beyond the arity are ignored. */
+implicants_status_t implicants_generate_blocks(
+    sample_block_fn_t sample, void* sample_base, report_fn_t report,
+    void* report_base, uint32_t arity);
+
+/* Opaque.  Create with implicants_collect, destroy with implicants_result_free. */
+typedef struct implicants_result implicants_result_t;
+
+/* Enumerates like implicants_generate, but stores the implicants in a new
+ * result, in the order they would have been reported, and points *out to
+ * it.  If primes_only is non-zero, only prime implicants are kept.
+ * On failure, *out is set to NULL. */
+implicants_status_t implicants_collect(
+    sample_fn_t sample, void* sample_base, uint32_t arity, int primes_only,
+    implicants_result_t** out);
+
+/* Number of implicants in the result.  Returns 0 for NULL. */
+size_t implicants_result_len(const implicants_result_t* result);
+
+/* Reads the i-th implicant.  Any of the output pointers may be NULL.
+ * Returns IMPLICANTS_INVALID_ARGUMENT if i is out of range. */
+implicants_status_t implicants_result_get(
+    const implicants_result_t* result, size_t i, uint32_t* mask_gap,
+    uint32_t* value, int* is_prime);
+
+/* Does nothing for NULL. */
+void implicants_result_free(implicants_result_t* result);
+
+/* Describes why the most recent call on this thread failed, or NULL if it
+ * succeeded.  The string is owned by the library, and stays valid until
+ * the next call into the library on this thread. */
+const char* implicants_last_error_message(void);
+
+/* Version of the library, e.g. "2.0.0".  The string is static. */
+const char* implicants_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* IMPLICANTS_C_HEADER */
@@ -0,0 +1,159 @@
+/* implicants – Enumerate (prime) implicants of an arbitrary function
+ * Copyright (C) 2017  Ben Wiederhake
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+/* Header-only C++11 convenience layer over implicants.h.
+ * Accepts arbitrary callables, and throws implicants::error on failure. */
+
+#ifndef IMPLICANTS_CPP_HEADER
+#define IMPLICANTS_CPP_HEADER
+
+#include <implicants.h>
+
+#include <cstddef>
+#include <cstdint>
+#include <exception>
+#include <stdexcept>
+#include <string>
+#include <type_traits>
+#include <vector>
+
+namespace implicants {
+
+struct cube {
+    /* Same encoding as for report_fn_t: gaps are 1 in mask_gap. */
+    uint32_t mask_gap;
+    uint32_t value;
+    bool is_prime;
+};
+
+inline bool operator==(const cube& a, const cube& b) {
+    return a.mask_gap == b.mask_gap && a.value == b.value && a.is_prime == b.is_prime;
+}
+
+inline bool operator!=(const cube& a, const cube& b) {
+    return !(a == b);
+}
+
+class error : public std::runtime_error {
+public:
+    error(implicants_status_t status, const std::string& what)
+        : std::runtime_error(what), status_(status) {}
+
+    implicants_status_t status() const { return status_; }
+
+private:
+    implicants_status_t status_;
+};
+
+namespace detail {
+
+/* Exceptions must not unwind through the library, so the trampolines
+ * park them here, and stop calling the user until generate returns. */
+template <typename Sample, typename Report>
+struct callbacks {
+    Sample& sample;
+    Report& report;
+    std::exception_ptr failure;
+};
+
+template <typename Sample, typename Report>
+int sample_trampoline(void* base, uint32_t v) {
+    callbacks<Sample, Report>& self = *static_cast<callbacks<Sample, Report>*>(base);
+    if (self.failure) {
+        return 0;
+    }
+    try {
+        return self.sample(v) ? 1 : 0;
+    } catch (...) {
+        self.failure = std::current_exception();
+        return 0;
+    }
+}
+
+template <typename Sample, typename Report>
+void report_trampoline(void* base, uint32_t m, uint32_t nonm, int is_prime) {
+    callbacks<Sample, Report>& self = *static_cast<callbacks<Sample, Report>*>(base);
+    if (self.failure) {
+        return;
+    }
+    try {
+        self.report(m, nonm, is_prime != 0);
+    } catch (...) {
+        self.failure = std::current_exception();
+    }
+}
+
+inline void check(implicants_status_t status) {
+    if (status != IMPLICANTS_OK) {
+        const char* message = implicants_last_error_message();
+        throw error(status, message ? message : "unknown error");
+    }
+}
+
+struct ignore_report {
+    void operator()(uint32_t, uint32_t, bool) const {}
+};
+
+} /* namespace detail */
+
+/* Calls sample(uint32_t) -> bool for every input, and
+ * report(uint32_t mask_gap, uint32_t value, bool is_prime) for every
+ * implicant.  Exceptions thrown by either are rethrown here. */
+template <typename Sample, typename Report>
+void generate(Sample&& sample, Report&& report, uint32_t arity) {
+    typedef typename std::remove_reference<Sample>::type S;
+    typedef typename std::remove_reference<Report>::type R;
+    detail::callbacks<S, R> self = { sample, report, std::exception_ptr() };
+    implicants_status_t status = implicants_generate(
+        &detail::sample_trampoline<S, R>, &self,
+        &detail::report_trampoline<S, R>, &self,
+        arity);
+    if (self.failure) {
+        std::rethrow_exception(self.failure);
+    }
+    detail::check(status);
+}
+
+/* All implicants (or only the prime ones), in the order they are found. */
+template <typename Sample>
+std::vector<cube> collect(Sample&& sample, uint32_t arity, bool primes_only = false) {
+    typedef typename std::remove_reference<Sample>::type S;
+    detail::ignore_report report;
+    detail::callbacks<S, detail::ignore_report> self = { sample, report, std::exception_ptr() };
+    implicants_result_t* result = NULL;
+    implicants_status_t status = implicants_collect(
+        &detail::sample_trampoline<S, detail::ignore_report>, &self,
+        arity, primes_only ? 1 : 0, &result);
+    if (self.failure) {
+        implicants_result_free(result);
+        std::rethrow_exception(self.failure);
+    }
+    detail::check(status);
+
+    std::vector<cube> cubes(implicants_result_len(result));
+    for (std::size_t i = 0; i < cubes.size(); ++i) {
+        int is_prime = 0;
+        implicants_result_get(result, i, &cubes[i].mask_gap, &cubes[i].value, &is_prime);
+        cubes[i].is_prime = is_prime != 0;
+    }
+    implicants_result_free(result);
+    return cubes;
+}
+
+} /* namespace implicants */
+
+#endif /* IMPLICANTS_CPP_HEADER */
@@ -0,0 +1,429 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! C interface to the `implicants` crate.  The build produces the static
+//! and shared libraries, and the `Makefile` in the top directory installs
+//! them together with the headers in `include/`.
+
+extern crate implicants_rs as implicants;
+extern crate libc;
+use libc::{c_char, c_int, c_void, size_t};
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use implicants::Implicant;
+
+// `build.rs` turns the public items of this file into `implicants.h` in
+// `OUT_DIR`, including their doc comments, up to any `# Safety` section.
+// `test_c_header` checks that `include/implicants.h` matches it.
+
+type SampleFnC = extern "C" fn(base: *mut c_void, v: u32) -> c_int;
+type ReportFnC = extern "C" fn(base: *mut c_void, m: u32, nonm: u32, is_prime: c_int);
+type SampleBlockFnC = extern "C" fn(base: *mut c_void, start: u32, out: *mut u64, len: size_t);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// E.g. a NULL callback, or an arity above 31.
+    InvalidArgument = 1,
+    /// Internal error.  Please report a bug.
+    Panic = 2,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Interior NULs would truncate the message anyway.
+    let message = CString::new(message.replace('\0', " ")).unwrap();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs `f` such that no panic can escape into C, and records
+/// the outcome for `implicants_last_error_message`.
+fn guarded<F: FnOnce() -> Result<(), (Status, String)>>(f: F) -> Status {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => Status::Ok,
+        Ok(Err((status, message))) => {
+            set_last_error(message);
+            status
+        }
+        Err(payload) => {
+            set_last_error(format!("panicked: {}", panic_message(&*payload)));
+            Status::Panic
+        }
+    }
+}
+
+fn check_arity(arity: u32) -> Result<(), (Status, String)> {
+    if arity >= 32 {
+        return Err((Status::InvalidArgument,
+                    format!("Can only handle at most 31 bits, but tried {} bits", arity)));
+    }
+    Ok(())
+}
+
+/// Callbacks must not unwind (C++ exceptions, longjmp) into the library.
+#[no_mangle]
+pub extern "C" fn implicants_generate(sample: Option<SampleFnC>,
+                                      sample_base: *mut c_void,
+                                      report: Option<ReportFnC>,
+                                      report_base: *mut c_void,
+                                      arity: u32)
+                                      -> Status {
+    guarded(|| {
+        let (sample, report) = match (sample, report) {
+            (Some(sample), Some(report)) => (sample, report),
+            _ => return Err((Status::InvalidArgument, "Callback is NULL".to_string())),
+        };
+        check_arity(arity)?;
+
+        let sample_wrapped = &|v| sample(sample_base, v) != 0;
+        let report_wrapped = &mut |m, nonm, prime| {
+            report(report_base, m, nonm, if prime {1} else {0});
+        };
+
+        implicants::generate(sample_wrapped, report_wrapped, arity);
+        Ok(())
+    })
+}
+
+struct BlockSamplerC {
+    sample: SampleBlockFnC,
+    base: *mut c_void,
+}
+
+impl implicants::BlockSampler for BlockSamplerC {
+    fn sample_block(&self, start: u32, out: &mut [u64]) {
+        (self.sample)(self.base, start, out.as_mut_ptr(), out.len());
+    }
+}
+
+/// Like `implicants_generate`, but `sample` fills `len` words at once: bit j
+/// of `out[i]` is the value at input `start + 64*i + j`.  The words are zero
+/// on entry, and bits for inputs beyond the arity are ignored.
+#[no_mangle]
+pub extern "C" fn implicants_generate_blocks(sample: Option<SampleBlockFnC>,
+                                             sample_base: *mut c_void,
+                                             report: Option<ReportFnC>,
+                                             report_base: *mut c_void,
+                                             arity: u32)
+                                             -> Status {
+    guarded(|| {
+        let (sample, report) = match (sample, report) {
+            (Some(sample), Some(report)) => (sample, report),
+            _ => return Err((Status::InvalidArgument, "Callback is NULL".to_string())),
+        };
+        check_arity(arity)?;
+
+        let sampler = BlockSamplerC { sample, base: sample_base };
+        let report_wrapped = &mut |m, nonm, prime| {
+            report(report_base, m, nonm, if prime {1} else {0});
+        };
+
+        implicants::generate_blocks(&sampler, report_wrapped, arity);
+        Ok(())
+    })
+}
+
+/// Opaque.  Create with `implicants_collect`, destroy with `implicants_result_free`.
+pub struct CollectedResult {
+    entries: Vec<(Implicant, bool)>,
+}
+
+/// Enumerates like `implicants_generate`, but stores the implicants in a new
+/// result, in the order they would have been reported, and points `*out` to
+/// it.  If `primes_only` is non-zero, only prime implicants are kept.
+/// On failure, `*out` is set to NULL.
+///
+/// # Safety
+///
+/// `out` must be NULL or valid for writing.
+#[no_mangle]
+pub unsafe extern "C" fn implicants_collect(sample: Option<SampleFnC>,
+                                            sample_base: *mut c_void,
+                                            arity: u32,
+                                            primes_only: c_int,
+                                            out: *mut *mut CollectedResult)
+                                            -> Status {
+    let out = match out.as_mut() {
+        Some(out) => out,
+        None => return guarded(|| Err((Status::InvalidArgument, "Output pointer is NULL".to_string()))),
+    };
+    *out = ptr::null_mut();
+    guarded(|| {
+        let sample = match sample {
+            Some(sample) => sample,
+            None => return Err((Status::InvalidArgument, "Callback is NULL".to_string())),
+        };
+        check_arity(arity)?;
+
+        let mut entries = Vec::new();
+        let sample_wrapped = &|v| sample(sample_base, v) != 0;
+        let report_wrapped = &mut |m, nonm, prime| {
+            if prime || primes_only == 0 {
+                entries.push((Implicant::new(m, nonm), prime));
+            }
+        };
+
+        implicants::generate(sample_wrapped, report_wrapped, arity);
+        *out = Box::into_raw(Box::new(CollectedResult { entries }));
+        Ok(())
+    })
+}
+
+/// Number of implicants in the result.  Returns 0 for NULL.
+///
+/// # Safety
+///
+/// `result` must come from `implicants_collect` and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn implicants_result_len(result: *const CollectedResult) -> size_t {
+    match result.as_ref() {
+        Some(result) => result.entries.len(),
+        None => 0,
+    }
+}
+
+/// Reads the `i`-th implicant.  Any of the output pointers may be NULL.
+/// Returns `IMPLICANTS_INVALID_ARGUMENT` if `i` is out of range.
+///
+/// # Safety
+///
+/// `result` must come from `implicants_collect` and not be freed yet,
+/// and all non-NULL output pointers must be valid for writing.
+#[no_mangle]
+pub unsafe extern "C" fn implicants_result_get(result: *const CollectedResult,
+                                               i: size_t,
+                                               mask_gap: *mut u32,
+                                               value: *mut u32,
+                                               is_prime: *mut c_int)
+                                               -> Status {
+    guarded(|| {
+        let result = match result.as_ref() {
+            Some(result) => result,
+            None => return Err((Status::InvalidArgument, "Result is NULL".to_string())),
+        };
+        let (imp, prime) = match result.entries.get(i) {
+            Some(&entry) => entry,
+            None => {
+                return Err((Status::InvalidArgument,
+                            format!("Index {} out of range, len is {}", i, result.entries.len())))
+            }
+        };
+        if let Some(mask_gap) = mask_gap.as_mut() {
+            *mask_gap = imp.mask_gap;
+        }
+        if let Some(value) = value.as_mut() {
+            *value = imp.value;
+        }
+        if let Some(is_prime) = is_prime.as_mut() {
+            *is_prime = prime as c_int;
+        }
+        Ok(())
+    })
+}
+
+/// Does nothing for NULL.
+///
+/// # Safety
+///
+/// `result` must come from `implicants_collect`, and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn implicants_result_free(result: *mut CollectedResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
+
+/// Describes why the most recent call on this thread failed, or NULL if it
+/// succeeded.  The string is owned by the library, and stays valid until
+/// the next call into the library on this thread.
+#[no_mangle]
+pub extern "C" fn implicants_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| match *e.borrow() {
+        Some(ref message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Version of the library, e.g. "2.0.0".  The string is static.
+#[no_mangle]
+pub extern "C" fn implicants_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+fn test_last_error() -> Option<String> {
+    let message = implicants_last_error_message();
+    if message.is_null() {
+        None
+    } else {
+        Some(unsafe { ::std::ffi::CStr::from_ptr(message) }.to_str().unwrap().to_string())
+    }
+}
+
+#[cfg(test)]
+extern "C" fn test_sample_c(_: *mut c_void, v: u32) -> c_int {
+    (v == 3) as c_int
+}
+
+#[cfg(test)]
+extern "C" fn test_report_c(base: *mut c_void, m: u32, nonm: u32, prime: c_int) {
+    let buffer = unsafe { &mut *(base as *mut Vec<(u32, u32, c_int)>) };
+    buffer.push((m, nonm, prime));
+}
+
+#[test]
+fn test_c_generate() {
+    let mut buffer: Vec<(u32, u32, c_int)> = Vec::new();
+    let status = implicants_generate(Some(test_sample_c), ptr::null_mut(),
+                                     Some(test_report_c), &mut buffer as *mut _ as *mut c_void,
+                                     2);
+    assert_eq!(Status::Ok, status);
+    assert_eq!(None, test_last_error());
+    assert_eq!(vec![(0, 0b11, 1)], buffer);
+}
+
+#[cfg(test)]
+extern "C" fn test_sample_block_c(_: *mut c_void, start: u32, out: *mut u64, len: size_t) {
+    let out = unsafe { ::std::slice::from_raw_parts_mut(out, len) };
+    for (i, word) in out.iter_mut().enumerate() {
+        for j in 0..64 {
+            if test_sample_c(ptr::null_mut(), start + 64 * i as u32 + j) != 0 {
+                *word |= 1 << j;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_c_generate_blocks() {
+    for &arity in &[3, 7] {
+        let mut expected: Vec<(u32, u32, c_int)> = Vec::new();
+        let expected_ptr = &mut expected as *mut _ as *mut c_void;
+        implicants_generate(Some(test_sample_c), ptr::null_mut(), Some(test_report_c), expected_ptr, arity);
+        let mut actual: Vec<(u32, u32, c_int)> = Vec::new();
+        let actual_ptr = &mut actual as *mut _ as *mut c_void;
+        let status = implicants_generate_blocks(Some(test_sample_block_c), ptr::null_mut(),
+                                                Some(test_report_c), actual_ptr, arity);
+        assert_eq!(Status::Ok, status);
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual);
+    }
+    assert_eq!(Status::InvalidArgument,
+               implicants_generate_blocks(None, ptr::null_mut(), Some(test_report_c), ptr::null_mut(), 3));
+}
+
+#[test]
+fn test_c_invalid() {
+    let status = implicants_generate(Some(test_sample_c), ptr::null_mut(),
+                                     Some(test_report_c), ptr::null_mut(),
+                                     32);
+    assert_eq!(Status::InvalidArgument, status);
+    assert_eq!(Some("Can only handle at most 31 bits, but tried 32 bits".to_string()),
+               test_last_error());
+
+    let status = implicants_generate(None, ptr::null_mut(),
+                                     Some(test_report_c), ptr::null_mut(),
+                                     3);
+    assert_eq!(Status::InvalidArgument, status);
+    assert_eq!(Some("Callback is NULL".to_string()), test_last_error());
+}
+
+#[test]
+fn test_c_panic() {
+    let status = guarded(|| panic!("Oh no {}", 42));
+    assert_eq!(Status::Panic, status);
+    assert_eq!(Some("panicked: Oh no 42".to_string()), test_last_error());
+    assert_eq!(Status::Ok, guarded(|| Ok(())));
+    assert_eq!(None, test_last_error());
+}
+
+#[cfg(test)]
+extern "C" fn test_sample_c11(_: *mut c_void, v: u32) -> c_int {
+    (v & 3 == 3) as c_int
+}
+
+#[test]
+fn test_c_collect() {
+    unsafe {
+        let mut result = ptr::null_mut();
+        assert_eq!(Status::Ok, implicants_collect(Some(test_sample_c11), ptr::null_mut(), 3, 0, &mut result));
+        assert!(!result.is_null());
+        assert_eq!(3, implicants_result_len(result));
+        let (mut m, mut v, mut p) = (0, 0, 0);
+        assert_eq!(Status::Ok, implicants_result_get(result, 1, &mut m, &mut v, &mut p));
+        assert_eq!((0, 0b111, 0), (m, v, p));
+        assert_eq!(Status::Ok, implicants_result_get(result, 2, &mut m, ptr::null_mut(), &mut p));
+        assert_eq!((0b100, 0b111, 1), (m, v, p));
+        assert_eq!(Status::InvalidArgument,
+                   implicants_result_get(result, 3, &mut m, &mut v, &mut p));
+        assert_eq!(Some("Index 3 out of range, len is 3".to_string()), test_last_error());
+        implicants_result_free(result);
+
+        assert_eq!(Status::Ok, implicants_collect(Some(test_sample_c11), ptr::null_mut(), 3, 1, &mut result));
+        assert_eq!(1, implicants_result_len(result));
+        assert_eq!(Status::Ok, implicants_result_get(result, 0, &mut m, &mut v, &mut p));
+        assert_eq!((0b100, 0b011, 1), (m, v, p));
+        implicants_result_free(result);
+
+        assert_eq!(Status::InvalidArgument,
+                   implicants_collect(Some(test_sample_c11), ptr::null_mut(), 32, 1, &mut result));
+        assert!(result.is_null());
+        assert_eq!(Some("Can only handle at most 31 bits, but tried 32 bits".to_string()),
+                   test_last_error());
+        assert_eq!(Status::InvalidArgument,
+                   implicants_collect(Some(test_sample_c11), ptr::null_mut(), 3, 1, ptr::null_mut()));
+        assert_eq!(0, implicants_result_len(ptr::null()));
+        implicants_result_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_c_version() {
+    let version = unsafe { ::std::ffi::CStr::from_ptr(implicants_version()) };
+    assert_eq!(env!("CARGO_PKG_VERSION"), version.to_str().unwrap());
+}
+
+#[test]
+fn test_c_header() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/implicants.h"));
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/implicants.h");
+    if ::std::env::var_os("IMPLICANTS_UPDATE_HEADER").is_some() {
+        ::std::fs::write(path, generated).unwrap();
+    }
+    let checked_in = ::std::fs::read_to_string(path).unwrap();
+    assert!(generated == checked_in,
+            "include/implicants.h is out of date; \
+             rerun the tests with IMPLICANTS_UPDATE_HEADER=1 to update it");
+}
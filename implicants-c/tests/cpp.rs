@@ -0,0 +1,72 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compiles `examples/wrapper.cpp` against the static library, and runs it.
+
+use std::env;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn main() {
+    // `cargo test` doesn't build the staticlib, so build it here.  It gets a
+    // target directory of its own, inside target/debug (or whichever profile
+    // this test was built with), as the running cargo may still hold the
+    // lock on the outer one.
+    let exe = env::current_exe().unwrap();
+    let profile_dir = exe.parent().unwrap().parent().unwrap();
+    let target_dir = profile_dir.join("cpp-test");
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "-p", "implicants-c", "--manifest-path"])
+        .arg(root.join("Cargo.toml"))
+        .arg("--target-dir").arg(&target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "Building the static library failed");
+    let lib_dir = target_dir.join("debug");
+    let binary = lib_dir.join("wrapper-cpp");
+
+    let cxx = env::var("CXX").unwrap_or_else(|_| "c++".to_string());
+    let status = Command::new(&cxx)
+        .arg("-o").arg(&binary)
+        .arg(root.join("examples").join("wrapper.cpp"))
+        .arg("-I").arg(root.join("include"))
+        // Not -limplicants, as that would prefer the shared library.
+        .arg(lib_dir.join("libimplicants.a"))
+        .args(["-lpthread", "-ldl", "-lm"])
+        .args(["-Wall", "-Wextra", "-pedantic", "-Werror", "-std=c++11"])
+        .status();
+    let status = match status {
+        Err(ref e) if e.kind() == ErrorKind::NotFound => {
+            println!("No C++ compiler '{}' found, skipping.", cxx);
+            return;
+        }
+        status => status.unwrap(),
+    };
+    assert!(status.success(), "Compiling wrapper.cpp failed");
+
+    let output = Command::new(&binary).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!("3 implicants\n\
+                00000000/00000000\n\
+                00000000/00000003\n\
+                00000000/00000006\n\
+                error 1: Can only handle at most 31 bits, but tried 32 bits\n\
+                sampling failed: no fives\n",
+               String::from_utf8(output.stdout).unwrap());
+}
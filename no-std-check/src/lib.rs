@@ -0,0 +1,38 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A `no_std` dependent, so that CI notices when `implicants` starts to
+//! need `std` without the `std` feature.  CI builds it for a target
+//! without `std`, e.g. with
+//! `cargo build -p implicants-no-std-check --target thumbv7m-none-eabi`.
+
+#![no_std]
+
+extern crate alloc;
+extern crate implicants;
+
+use alloc::vec::Vec;
+use implicants::minimize::{minimize, CostModel};
+use implicants::{generate, Cover, Implicant};
+
+/// The primes of `sampling_fn`, and a minimal cover.
+pub fn primes_and_cover(sampling_fn: &dyn Fn(u32) -> bool, arity: u32) -> (Vec<Implicant>, Cover) {
+    let mut primes = Vec::new();
+    generate(sampling_fn, &mut |m, v, prime| if prime {
+        primes.push(Implicant::new(m, v));
+    }, arity);
+    (primes, minimize(sampling_fn, arity, &CostModel::uniform(arity, 1, 1)))
+}
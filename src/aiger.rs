@@ -0,0 +1,228 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export of covers as a combinational and-inverter graph in the AIGER
+//! format (both `aag` and `aig`).
+//!
+//! Literals follow the AIGER convention: `0` is false, `1` is true,
+//! variable `v` has the literals `2v` (plain) and `2v + 1` (inverted).
+//! Inputs are the variables `1..=I`, in bit order.
+
+use std::collections::HashMap;
+use implicant::{low_mask, Implicant};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Aig {
+    pub inputs: u32,
+    /// `ands[k]` is the variable `inputs + k + 1`, with two child literals.
+    /// Children always refer to smaller variables.
+    pub ands: Vec<(u32, u32)>,
+    pub outputs: Vec<u32>,
+    /// Either empty, or one name per input.  Written as symbol table.
+    pub input_names: Vec<String>,
+    /// Either empty, or one name per output.  Written as symbol table.
+    pub output_names: Vec<String>,
+}
+
+struct Builder {
+    inputs: u32,
+    ands: Vec<(u32, u32)>,
+    /// Structural hashing, so identical (sub-)products are shared.
+    strash: HashMap<(u32, u32), u32>,
+}
+
+impl Builder {
+    fn and(&mut self, a: u32, b: u32) -> u32 {
+        let (hi, lo) = if a >= b { (a, b) } else { (b, a) };
+        if lo == 0 || hi == lo ^ 1 {
+            return 0;
+        }
+        if lo == 1 || hi == lo {
+            return hi;
+        }
+        if let Some(&lit) = self.strash.get(&(hi, lo)) {
+            return lit;
+        }
+        self.ands.push((hi, lo));
+        let lit = 2 * (self.inputs + self.ands.len() as u32);
+        self.strash.insert((hi, lo), lit);
+        lit
+    }
+
+    fn or(&mut self, a: u32, b: u32) -> u32 {
+        self.and(a ^ 1, b ^ 1) ^ 1
+    }
+
+    fn product(&mut self, imp: &Implicant) -> u32 {
+        let mut acc = 1;
+        for i in 0..self.inputs {
+            if imp.mask_gap & (1 << i) == 0 {
+                let lit = 2 * (i + 1) + if imp.value & (1 << i) != 0 { 0 } else { 1 };
+                acc = self.and(acc, lit);
+            }
+        }
+        acc
+    }
+}
+
+impl Aig {
+    /// Builds the graph where output `o` is the disjunction of `covers[o]`.
+    /// Equal products (and equal prefixes of products) are only built once.
+    pub fn from_covers(inputs: u32, covers: &[&[Implicant]]) -> Aig {
+        assert!(inputs < 32, "Can only handle at most 31 bits, but tried {} bits", inputs);
+        let mut builder = Builder {
+            inputs,
+            ands: Vec::new(),
+            strash: HashMap::new(),
+        };
+        let mut outputs = Vec::new();
+        for cover in covers {
+            let mut acc = 0;
+            for imp in cover.iter() {
+                assert_eq!(0, imp.value & !low_mask(inputs),
+                           "Implicant {:?} uses more than {} variables", imp, inputs);
+                let product = builder.product(imp);
+                acc = builder.or(acc, product);
+            }
+            outputs.push(acc);
+        }
+        Aig {
+            inputs,
+            ands: builder.ands,
+            outputs,
+            input_names: Vec::new(),
+            output_names: Vec::new(),
+        }
+    }
+
+    /// Highest variable index, i.e., `M` in the header.
+    pub fn max_var(&self) -> u32 {
+        self.inputs + self.ands.len() as u32
+    }
+
+    /// Simulates the graph on a single input.
+    pub fn eval(&self, point: u32) -> Vec<bool> {
+        let mut values = vec![false; self.max_var() as usize + 1];
+        for i in 0..self.inputs {
+            values[i as usize + 1] = point & (1 << i) != 0;
+        }
+        let lit = |values: &[bool], lit: u32| values[(lit / 2) as usize] ^ (lit & 1 == 1);
+        for (k, &(a, b)) in self.ands.iter().enumerate() {
+            values[self.inputs as usize + k + 1] = lit(&values, a) && lit(&values, b);
+        }
+        self.outputs.iter().map(|&o| lit(&values, o)).collect()
+    }
+
+    fn header(&self, magic: &str) -> String {
+        format!("{} {} {} 0 {} {}\n",
+                magic, self.max_var(), self.inputs, self.outputs.len(), self.ands.len())
+    }
+
+    fn symbols(&self) -> String {
+        let mut out = String::new();
+        for (i, name) in self.input_names.iter().enumerate() {
+            out.push_str(&format!("i{} {}\n", i, name));
+        }
+        for (o, name) in self.output_names.iter().enumerate() {
+            out.push_str(&format!("o{} {}\n", o, name));
+        }
+        out
+    }
+
+    /// The ASCII format (`aag`).
+    pub fn to_ascii(&self) -> String {
+        let mut out = self.header("aag");
+        for i in 0..self.inputs {
+            out.push_str(&format!("{}\n", 2 * (i + 1)));
+        }
+        for o in &self.outputs {
+            out.push_str(&format!("{}\n", o));
+        }
+        for (k, &(a, b)) in self.ands.iter().enumerate() {
+            out.push_str(&format!("{} {} {}\n", 2 * (self.inputs + k as u32 + 1), a, b));
+        }
+        out.push_str(&self.symbols());
+        out
+    }
+
+    /// The binary format (`aig`).
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut out = self.header("aig").into_bytes();
+        for o in &self.outputs {
+            out.extend(format!("{}\n", o).bytes());
+        }
+        for (k, &(a, b)) in self.ands.iter().enumerate() {
+            let lhs = 2 * (self.inputs + k as u32 + 1);
+            push_delta(&mut out, lhs - a);
+            push_delta(&mut out, a - b);
+        }
+        out.extend(self.symbols().bytes());
+        out
+    }
+}
+
+/// Variable-length encoding of the binary format: 7 bits per byte,
+/// least significant group first, MSB set on all but the last byte.
+fn push_delta(out: &mut Vec<u8>, mut delta: u32) {
+    while delta >= 0x80 {
+        out.push((delta & 0x7f) as u8 | 0x80);
+        delta >>= 7;
+    }
+    out.push(delta as u8);
+}
+
+#[test]
+fn test_and_gate() {
+    let cover = [Implicant::new(0, 0b11)];
+    let aig = Aig::from_covers(2, &[&cover]);
+    assert_eq!("aag 3 2 0 1 1\n2\n4\n6\n6 4 2\n", aig.to_ascii());
+    assert_eq!(b"aig 3 2 0 1 1\n6\n\x02\x02".to_vec(), aig.to_binary());
+}
+
+#[test]
+fn test_constants() {
+    let aig = Aig::from_covers(2, &[&[], &[Implicant::new(0b11, 0)]]);
+    assert_eq!(vec![0, 1], aig.outputs);
+    assert!(aig.ands.is_empty());
+}
+
+#[test]
+fn test_shared() {
+    // f = a·b·c' + a', g = a·b·c' + c
+    let f = [Implicant::new(0, 0b011), Implicant::new(0b110, 0)];
+    let g = [Implicant::new(0, 0b011), Implicant::new(0b011, 0b100)];
+    let mut aig = Aig::from_covers(3, &[&f, &g]);
+    // Two ANDs for the shared product, one for each OR.
+    assert_eq!(4, aig.ands.len());
+    for v in 0..8 {
+        let expect_f = f.iter().any(|imp| imp.contains(v));
+        let expect_g = g.iter().any(|imp| imp.contains(v));
+        assert_eq!(vec![expect_f, expect_g], aig.eval(v));
+    }
+
+    aig.input_names = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    aig.output_names = vec!["f".to_string(), "g".to_string()];
+    assert!(aig.to_ascii().ends_with("i0 a\ni1 b\ni2 c\no0 f\no1 g\n"));
+}
+
+#[test]
+fn test_delta() {
+    let mut out = Vec::new();
+    push_delta(&mut out, 0x7f);
+    push_delta(&mut out, 0x80);
+    push_delta(&mut out, 16387);
+    assert_eq!(vec![0x7f, 0x80, 0x01, 0x83, 0x80, 0x01], out);
+}
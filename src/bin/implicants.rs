@@ -0,0 +1,425 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Command-line frontend for quick experiments.
+
+extern crate implicants;
+
+use std::env;
+use std::fs;
+use std::process;
+use std::rc::Rc;
+use implicants::Implicant;
+use implicants::expr::Expression;
+use implicants::minimize::{minimize_with_dc, CostModel};
+use implicants::pla::Pla;
+
+const USAGE: &str = "\
+Usage: implicants [all|primes|minimize] SOURCE [OPTIONS]
+
+Commands:
+    all                 Enumerate all implicants
+    primes              Enumerate prime implicants (default)
+    minimize            Compute a cheap cover, where each product term and
+                        each literal costs 1
+
+Sources (exactly one):
+    --hex TABLE         Truth table as hex number, bit v is f(v)
+    --minterms LIST     Comma-separated ON-set, needs --arity
+    --pla FILE          Berkeley PLA file, all outputs
+    --expr EXPRESSION   Boolean expression, like 'a & !b | c'
+
+Options:
+    --dont-cares LIST   Comma-separated don't-cares (with --minterms)
+    --arity N           Number of input variables
+    --names A,B,...     Names of the input variables, in bit order
+    --output N          Only consider output N of a PLA file
+    --format FORMAT     cube (default), json or pla
+    --order ORDER       cube (default), size or reported
+    --max-literals N    Only print implicants with at most N literals
+    --help              Print this help
+
+Cubes are printed with the least significant bit first, like in PLA files.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    All,
+    Primes,
+    Minimize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Cube,
+    Json,
+    Pla,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Cube,
+    Size,
+    Reported,
+}
+
+enum Source {
+    Hex(String),
+    Minterms(String),
+    Pla(String),
+    Expr(String),
+}
+
+struct Config {
+    mode: Mode,
+    source: Source,
+    dont_cares: Option<String>,
+    arity: Option<u32>,
+    names: Option<Vec<String>>,
+    output: Option<u32>,
+    format: Format,
+    order: Order,
+    max_literals: Option<u32>,
+}
+
+/// A single output, with its ON-set and don't-care set.
+struct Function {
+    name: String,
+    on: Box<dyn Fn(u32) -> bool>,
+    dc: Box<dyn Fn(u32) -> bool>,
+}
+
+struct Problem {
+    arity: u32,
+    names: Vec<String>,
+    functions: Vec<Function>,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("implicants: {}", message);
+    eprintln!("Try 'implicants --help' for more information.");
+    process::exit(2);
+}
+
+fn parse_number(what: &str, text: &str) -> u32 {
+    text.trim().parse().unwrap_or_else(|_| fail(&format!("invalid {}: '{}'", what, text)))
+}
+
+fn parse_list(text: &str) -> Vec<u32> {
+    text.split(',').filter(|s| !s.trim().is_empty()).map(|s| parse_number("number", s)).collect()
+}
+
+fn parse_args(args: Vec<String>) -> Config {
+    let mut config = Config {
+        mode: Mode::Primes,
+        source: Source::Hex(String::new()),
+        dont_cares: None,
+        arity: None,
+        names: None,
+        output: None,
+        format: Format::Cube,
+        order: Order::Cube,
+        max_literals: None,
+    };
+    let mut source = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next().unwrap_or_else(|| fail(&format!("{} needs an argument", name)))
+        };
+        match arg.as_str() {
+            "all" => config.mode = Mode::All,
+            "primes" => config.mode = Mode::Primes,
+            "minimize" => config.mode = Mode::Minimize,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--hex" | "--minterms" | "--pla" | "--expr" => {
+                if source.is_some() {
+                    fail("more than one source given");
+                }
+                let v = value(&arg);
+                source = Some(match arg.as_str() {
+                    "--hex" => Source::Hex(v),
+                    "--minterms" => Source::Minterms(v),
+                    "--pla" => Source::Pla(v),
+                    _ => Source::Expr(v),
+                });
+            }
+            "--dont-cares" => config.dont_cares = Some(value(&arg)),
+            "--arity" => config.arity = Some(parse_number("arity", &value(&arg))),
+            "--names" => {
+                config.names = Some(value(&arg).split(',').map(String::from).collect())
+            }
+            "--output" => config.output = Some(parse_number("output", &value(&arg))),
+            "--max-literals" => {
+                config.max_literals = Some(parse_number("literal count", &value(&arg)))
+            }
+            "--format" => {
+                config.format = match value(&arg).as_str() {
+                    "cube" => Format::Cube,
+                    "json" => Format::Json,
+                    "pla" => Format::Pla,
+                    other => fail(&format!("unknown format '{}'", other)),
+                }
+            }
+            "--order" => {
+                config.order = match value(&arg).as_str() {
+                    "cube" => Order::Cube,
+                    "size" => Order::Size,
+                    "reported" => Order::Reported,
+                    other => fail(&format!("unknown order '{}'", other)),
+                }
+            }
+            other => fail(&format!("unknown argument '{}'", other)),
+        }
+    }
+    config.source = source.unwrap_or_else(|| fail("no source given"));
+    config
+}
+
+fn table_of(bits: Vec<bool>) -> Box<dyn Fn(u32) -> bool> {
+    Box::new(move |v| bits[v as usize])
+}
+
+fn table_from_list(arity: u32, list: &str) -> Vec<bool> {
+    let mut table = vec![false; 1 << arity];
+    for v in parse_list(list) {
+        if v as usize >= table.len() {
+            fail(&format!("minterm {} is out of range for arity {}", v, arity));
+        }
+        table[v as usize] = true;
+    }
+    table
+}
+
+fn load(config: &Config) -> Problem {
+    if config.dont_cares.is_some() && !matches!(config.source, Source::Minterms(_)) {
+        fail("--dont-cares only works with --minterms");
+    }
+
+    let mut problem = match config.source {
+        Source::Hex(ref text) => {
+            let digits = text.trim_start_matches("0x");
+            let mut bits = Vec::new();
+            for c in digits.chars().rev() {
+                let d = c.to_digit(16).unwrap_or_else(|| fail(&format!("invalid hex digit '{}'", c)));
+                bits.extend((0..4).map(|i| (d >> i) & 1 == 1));
+            }
+            let arity = match config.arity {
+                Some(arity) => arity,
+                None if bits.len().is_power_of_two() => bits.len().trailing_zeros(),
+                None => fail("table length is not a power of two, please give --arity"),
+            };
+            if arity >= 32 || (1usize << arity) > bits.len() {
+                fail(&format!("table is too short for arity {}", arity));
+            }
+            if bits[(1 << arity)..].iter().any(|&b| b) {
+                fail(&format!("table has bits set beyond arity {}", arity));
+            }
+            Problem {
+                arity,
+                names: Vec::new(),
+                functions: vec![Function {
+                    name: "f".to_string(),
+                    on: table_of(bits),
+                    dc: Box::new(|_| false),
+                }],
+            }
+        }
+        Source::Minterms(ref text) => {
+            let arity = config.arity.unwrap_or_else(|| fail("--minterms needs --arity"));
+            if arity >= 32 {
+                fail("can only handle at most 31 variables");
+            }
+            let on = table_from_list(arity, text);
+            let dc = match config.dont_cares {
+                Some(ref list) => table_from_list(arity, list),
+                None => vec![false; 1 << arity],
+            };
+            Problem {
+                arity,
+                names: Vec::new(),
+                functions: vec![Function {
+                    name: "f".to_string(),
+                    on: table_of(on),
+                    dc: table_of(dc),
+                }],
+            }
+        }
+        Source::Pla(ref path) => {
+            let text = fs::read_to_string(path)
+                .unwrap_or_else(|e| fail(&format!("cannot read {}: {}", path, e)));
+            let pla = Pla::parse(&text).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+            let pla = Rc::new(pla);
+            let functions = (0..pla.outputs)
+                .map(|o| {
+                    let (p_on, p_dc) = (pla.clone(), pla.clone());
+                    Function {
+                        name: pla.output_labels.get(o as usize).cloned()
+                            .unwrap_or_else(|| format!("f{}", o)),
+                        on: Box::new(move |v| p_on.value(o, v) == Some(true)),
+                        dc: Box::new(move |v| p_dc.value(o, v).is_none()),
+                    }
+                })
+                .collect();
+            Problem {
+                arity: pla.inputs,
+                names: pla.input_labels.clone(),
+                functions,
+            }
+        }
+        Source::Expr(ref text) => {
+            let expr = Expression::parse(text)
+                .unwrap_or_else(|e| fail(&format!("in expression: {}", e)));
+            let names = expr.names().to_vec();
+            Problem {
+                arity: expr.arity(),
+                names,
+                functions: vec![Function {
+                    name: "f".to_string(),
+                    on: Box::new(move |v| expr.eval(v)),
+                    dc: Box::new(|_| false),
+                }],
+            }
+        }
+    };
+
+    if let Some(arity) = config.arity {
+        if arity != problem.arity {
+            fail(&format!("source has {} inputs, but --arity is {}", problem.arity, arity));
+        }
+    }
+    if let Some(ref names) = config.names {
+        problem.names = names.clone();
+    }
+    if !problem.names.is_empty() && problem.names.len() != problem.arity as usize {
+        fail(&format!("got {} names for {} inputs", problem.names.len(), problem.arity));
+    }
+    if let Some(o) = config.output {
+        if o as usize >= problem.functions.len() {
+            fail(&format!("there is no output {}", o));
+        }
+        let f = problem.functions.swap_remove(o as usize);
+        problem.functions = vec![f];
+    }
+    problem
+}
+
+/// Returns the requested implicants, and whether each is prime.
+fn run(config: &Config, arity: u32, function: &Function) -> Vec<(Implicant, bool)> {
+    let mut result = Vec::new();
+    match config.mode {
+        Mode::All | Mode::Primes => {
+            let mut collect = |mask_gap: u32, value: u32, is_prime: bool| {
+                if is_prime || config.mode == Mode::All {
+                    result.push((Implicant::new(mask_gap, value), is_prime));
+                }
+            };
+            implicants::generate(&|v| (function.on)(v) || (function.dc)(v), &mut collect, arity);
+        }
+        Mode::Minimize => {
+            let cover = minimize_with_dc(&*function.on,
+                                         &*function.dc,
+                                         arity,
+                                         &CostModel::uniform(arity, 1, 1));
+            result.extend(cover.iter().map(|&imp| (imp, true)));
+        }
+    }
+
+    if let Some(max) = config.max_literals {
+        result.retain(|&(imp, _)| imp.literals(arity) <= max);
+    }
+    match config.order {
+        Order::Cube => result.sort_by_key(|&(imp, _)| imp.to_cube_string(arity)),
+        Order::Size => result.sort_by_key(|&(imp, _)| (imp.literals(arity), imp.to_cube_string(arity))),
+        Order::Reported => {}
+    }
+    result
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn print_json(problem: &Problem, results: &[Vec<(Implicant, bool)>]) {
+    let names: Vec<String> = problem.names.iter().map(|n| json_string(n)).collect();
+    println!("{{");
+    println!("  \"arity\": {},", problem.arity);
+    println!("  \"names\": [{}],", names.join(", "));
+    println!("  \"outputs\": [");
+    for (i, (function, result)) in problem.functions.iter().zip(results).enumerate() {
+        println!("    {{");
+        println!("      \"name\": {},", json_string(&function.name));
+        println!("      \"implicants\": [");
+        for (j, &(imp, is_prime)) in result.iter().enumerate() {
+            println!("        {{\"cube\": \"{}\", \"mask_gap\": {}, \"value\": {}, \"prime\": {}}}{}",
+                     imp.to_cube_string(problem.arity),
+                     imp.mask_gap,
+                     imp.value,
+                     is_prime,
+                     if j + 1 < result.len() { "," } else { "" });
+        }
+        println!("      ]");
+        println!("    }}{}", if i + 1 < results.len() { "," } else { "" });
+    }
+    println!("  ]");
+    println!("}}");
+}
+
+fn main() {
+    let config = parse_args(env::args().skip(1).collect());
+    let problem = load(&config);
+    let results: Vec<Vec<(Implicant, bool)>> = problem.functions
+        .iter()
+        .map(|f| run(&config, problem.arity, f))
+        .collect();
+
+    match config.format {
+        Format::Cube => {
+            for (function, result) in problem.functions.iter().zip(&results) {
+                if problem.functions.len() > 1 {
+                    println!("# {}", function.name);
+                }
+                for &(imp, is_prime) in result {
+                    let suffix = if config.mode == Mode::All && is_prime { " prime" } else { "" };
+                    println!("{}{}", imp.to_cube_string(problem.arity), suffix);
+                }
+            }
+        }
+        Format::Json => print_json(&problem, &results),
+        Format::Pla => {
+            let covers: Vec<Vec<Implicant>> = results.iter()
+                .map(|r| r.iter().map(|&(imp, _)| imp).collect())
+                .collect();
+            let cover_refs: Vec<&[Implicant]> = covers.iter().map(|c| c.as_slice()).collect();
+            let mut pla = Pla::from_covers(problem.arity, &cover_refs);
+            pla.input_labels = problem.names.clone();
+            pla.output_labels = problem.functions.iter().map(|f| f.name.clone()).collect();
+            print!("{}", pla);
+        }
+    }
+}
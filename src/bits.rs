@@ -17,37 +17,69 @@
 //! Thin layer of arbitrary bitset implementation.
 //! I want to be able to replace it easily.
 
-extern crate bit_vec;
+use prelude::*;
 
 pub struct Bitset {
-    backing: bit_vec::BitVec,
+    backing: Vec<u64>,
+    len: usize,
     any: bool,
 }
 
 impl Bitset {
-    pub fn of(nbits: u32) -> Self {
+    /// Returns `None` if the allocation fails.
+    pub fn try_of(nbits: u32) -> Option<Self> {
         assert!(nbits < 32,
                 "Can only handle at most 31 bits, but tried {} bits",
                 nbits);
         // I could probably extend that to include 32, but then this would overflow on x86:
-        let len = (1usize << nbits) as usize;
-        Bitset {
-            backing: bit_vec::BitVec::from_elem(len, false),
-            any: false,
+        let len = 1usize << nbits;
+        let words = len.div_ceil(64);
+        let mut backing = Vec::new();
+        if backing.try_reserve_exact(words).is_err() {
+            return None;
         }
+        backing.resize(words, 0);
+        Some(Bitset {
+            backing,
+            len,
+            any: false,
+        })
+    }
+
+    /// How much memory a `Bitset::try_of(nbits)` occupies.
+    pub fn bytes_for(nbits: u32) -> usize {
+        (1usize << nbits).div_ceil(64) * 8
     }
 
     pub fn set(&mut self, mask: u32) {
-        self.backing.set(mask as usize, true);
+        assert!((mask as usize) < self.len,
+                "Accessed {}, but len is only {}",
+                mask,
+                self.len);
+        self.backing[(mask >> 6) as usize] |= 1 << (mask & 63);
         self.any = true;
     }
 
     pub fn is(&self, mask: u32) -> bool {
-        assert!((mask as usize) < self.backing.len(),
+        assert!((mask as usize) < self.len,
                 "Accessed {}, but len is only {}",
                 mask,
-                self.backing.len());
-        self.backing.get(mask as usize).unwrap()
+                self.len);
+        (self.backing[(mask >> 6) as usize] >> (mask & 63)) & 1 == 1
+    }
+
+    /// Lets `fill` set whole words at once.  Bits beyond `len` are cleared.
+    pub fn fill_words<F: FnOnce(&mut [u64])>(&mut self, fill: F) {
+        fill(&mut self.backing);
+        if self.len < 64 {
+            self.backing[0] &= (1 << self.len) - 1;
+        }
+        self.any = self.backing.iter().any(|&word| word != 0);
+    }
+
+    #[cfg(feature = "std")]
+    pub fn words(&self) -> &[u64] {
+        &self.backing
     }
 
     pub fn is_any(&self) -> bool {
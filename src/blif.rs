@@ -0,0 +1,120 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export of covers as a BLIF model, with one `.names` block per output.
+
+use implicant::{low_mask, Cover, Implicant};
+use variables::Variables;
+
+/// Writes a combinational BLIF model, where output `o` is called
+/// `output_names[o]` and is the disjunction of `covers[o]`.
+/// Each `.names` block only lists the inputs that its output depends on.
+pub fn write(model: &str,
+             input_names: &[&str],
+             output_names: &[&str],
+             covers: &[&[Implicant]])
+             -> String {
+    assert_eq!(output_names.len(), covers.len(), "Need exactly one name per output");
+    let arity = input_names.len() as u32;
+    let mut out = String::new();
+    out.push_str(&format!(".model {}\n", model));
+    out.push_str(&format!(".inputs {}\n", input_names.join(" ")));
+    out.push_str(&format!(".outputs {}\n", output_names.join(" ")));
+
+    for (name, cover) in output_names.iter().zip(covers) {
+        let support: Vec<u32> = (0..arity)
+            .filter(|&i| cover.iter().any(|imp| imp.mask_gap & (1 << i) == 0))
+            .collect();
+        out.push_str(".names");
+        for &i in &support {
+            out.push(' ');
+            out.push_str(input_names[i as usize]);
+        }
+        out.push_str(&format!(" {}\n", name));
+
+        for imp in cover.iter() {
+            assert_eq!(0, imp.value & !low_mask(arity),
+                       "Implicant {:?} uses more than {} variables", imp, arity);
+            for &i in &support {
+                out.push(if imp.mask_gap & (1 << i) != 0 {
+                    '-'
+                } else if imp.value & (1 << i) != 0 {
+                    '1'
+                } else {
+                    '0'
+                });
+            }
+            // A constant-true block is just "1".
+            if !support.is_empty() {
+                out.push(' ');
+            }
+            out.push_str("1\n");
+        }
+    }
+
+    out.push_str(".end\n");
+    out
+}
+
+/// Like `write`, but takes the input names from `variables`, and inverts
+/// the literals of active-low variables.
+pub fn write_named(model: &str,
+                   variables: &Variables,
+                   output_names: &[&str],
+                   covers: &[&Cover])
+                   -> String {
+    let signals: Vec<Cover> = covers.iter().map(|cover| variables.cover_to_signals(cover)).collect();
+    let slices: Vec<&[Implicant]> = signals.iter().map(|cover| &cover[..]).collect();
+    write(model, &variables.names(), output_names, &slices)
+}
+
+#[test]
+fn test_write() {
+    // f = a·b' + c, g = 0, h = 1
+    let f = [Implicant::new(0b100, 0b001), Implicant::new(0b011, 0b100)];
+    let h = [Implicant::new(0b111, 0)];
+    let text = write("test", &["a", "b", "c"], &["f", "g", "h"], &[&f, &[], &h]);
+    assert_eq!("\
+.model test
+.inputs a b c
+.outputs f g h
+.names a b c f
+10- 1
+--1 1
+.names g
+.names h
+1
+.end
+", text);
+}
+
+#[test]
+fn test_write_support() {
+    // f = b
+    let f = [Implicant::new(0b101, 0b010)];
+    let text = write("m", &["a", "b", "c"], &["f"], &[&f]);
+    assert!(text.contains(".names b f\n1 1\n"));
+}
+
+#[test]
+fn test_write_named() {
+    use variables::Variable;
+    let vars = Variables::new(vec![Variable::new("a", 0), Variable::active_low("b_n", 1)]).unwrap();
+    let f = Cover::new(2, vec![Implicant::new(0, 0b11)]);
+    let text = write_named("test", &vars, &["f"], &[&f]);
+    assert!(text.contains(".inputs a b_n\n"));
+    assert!(text.contains(".names a b_n f\n10 1\n"));
+}
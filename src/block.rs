@@ -0,0 +1,83 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sampling many inputs per call.
+
+/// How many words `generate_blocks` asks for at once, at most.
+pub const BLOCK_WORDS: usize = 1024;
+
+/// A function that can be sampled in blocks of 64 inputs per word.
+pub trait BlockSampler {
+    /// Bit `j` of `out[i]` must be set to the value at input
+    /// `start + 64 * i + j`.  `start` is a multiple of 64.  Bits for
+    /// inputs beyond the arity are ignored, and `out` is zeroed on entry.
+    fn sample_block(&self, start: u32, out: &mut [u64]);
+}
+
+/// Adapts a per-input closure to `BlockSampler`.
+pub struct PerPoint<F> {
+    sampling_fn: F,
+    arity: u32,
+}
+
+impl<F: Fn(u32) -> bool> PerPoint<F> {
+    /// `sampling_fn` is only ever called for inputs below `2^arity`.
+    pub fn new(sampling_fn: F, arity: u32) -> Self {
+        PerPoint { sampling_fn, arity }
+    }
+}
+
+impl<F: Fn(u32) -> bool> BlockSampler for PerPoint<F> {
+    fn sample_block(&self, start: u32, out: &mut [u64]) {
+        let end = 1u64 << self.arity;
+        for (i, word) in out.iter_mut().enumerate() {
+            let base = start as u64 + 64 * i as u64;
+            for j in 0..64.min(end.saturating_sub(base)) {
+                if (self.sampling_fn)((base + j) as u32) {
+                    *word |= 1 << j;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+struct TestOddWords;
+
+#[cfg(test)]
+impl BlockSampler for TestOddWords {
+    fn sample_block(&self, start: u32, out: &mut [u64]) {
+        for (i, word) in out.iter_mut().enumerate() {
+            // Inputs 64..128, 192..256, etc.
+            if (start / 64 + i as u32) % 2 == 1 {
+                *word = !0;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_block_sampler() {
+    // True iff bit 6 is set.
+    let mut primes = Vec::new();
+    ::generate_blocks(&TestOddWords, &mut |m, v, p| if p { primes.push((m, v)) }, 8);
+    assert_eq!(vec![(0b1011_1111, 0b0100_0000)], primes);
+
+    // The words beyond arity 3 are ignored.
+    let mut all = Vec::new();
+    ::generate_blocks(&TestOddWords, &mut |m, v, p| all.push((m, v, p)), 3);
+    assert!(all.is_empty());
+}
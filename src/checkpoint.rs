@@ -0,0 +1,255 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! On-disk format of `Checkpoint`.
+//!
+//! All numbers are little-endian:
+//!
+//! - magic `b"IMPLCKPT"`, then `u32` format version, currently 1
+//! - `u32` arity, `u32` rank
+//! - `u64` implicants, `u64` primes, `u64` peak bytes so far
+//! - `u64` number of chunks, then for each chunk in ascending order:
+//!   `u32` mask of the chunk, and the `2^arity` bits of the chunk,
+//!   as `max(1, 2^arity / 64)` words of `u64`
+//! - `u64` FNV-1a hash of all of the above
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use bits::Bitset;
+use {Checkpoint, Statistics};
+
+const MAGIC: &[u8; 8] = b"IMPLCKPT";
+const VERSION: u32 = 1;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash = (hash ^ b as u64).wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+struct HashingWriter<W> {
+    inner: W,
+    hash: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn put(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.hash = fnv1a(self.hash, bytes);
+        self.inner.write_all(bytes)
+    }
+}
+
+struct HashingReader<R> {
+    inner: R,
+    hash: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.inner.read_exact(&mut bytes)?;
+        self.hash = fnv1a(self.hash, &bytes);
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Checkpoint {
+    pub fn write_to(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let mut w = HashingWriter {
+            inner: writer,
+            hash: FNV_OFFSET,
+        };
+        w.put(MAGIC)?;
+        w.put(&VERSION.to_le_bytes())?;
+        w.put(&self.arity.to_le_bytes())?;
+        w.put(&self.rank.to_le_bytes())?;
+        w.put(&self.stats.implicants.to_le_bytes())?;
+        w.put(&self.stats.primes.to_le_bytes())?;
+        w.put(&(self.stats.peak_bytes as u64).to_le_bytes())?;
+        w.put(&(self.chunks.len() as u64).to_le_bytes())?;
+        let mut masks: Vec<u32> = self.chunks.keys().cloned().collect();
+        masks.sort();
+        for mask in masks {
+            w.put(&mask.to_le_bytes())?;
+            for word in self.chunks[&mask].words() {
+                w.put(&word.to_le_bytes())?;
+            }
+        }
+        let hash = w.hash;
+        w.inner.write_all(&hash.to_le_bytes())
+    }
+
+    /// Fails with `io::ErrorKind::InvalidData` if the data is not a
+    /// checkpoint of a supported version, or is damaged.
+    pub fn read_from(reader: &mut dyn Read) -> io::Result<Checkpoint> {
+        let mut r = HashingReader {
+            inner: reader,
+            hash: FNV_OFFSET,
+        };
+        if &r.take::<8>()? != MAGIC {
+            return Err(invalid("Not a checkpoint".to_string()));
+        }
+        let version = r.u32()?;
+        if version != VERSION {
+            return Err(invalid(format!("Unsupported checkpoint version {}", version)));
+        }
+        let arity = r.u32()?;
+        let rank = r.u32()?;
+        if arity >= 32 || rank > arity {
+            return Err(invalid(format!("Bad rank {} or arity {}", rank, arity)));
+        }
+        let stats = Statistics {
+            implicants: r.u64()?,
+            primes: r.u64()?,
+            peak_bytes: r.u64()? as usize,
+        };
+        let count = r.u64()?;
+        let mut chunks = HashMap::new();
+        for _ in 0..count {
+            let mask = r.u32()?;
+            if mask.count_ones() != rank || mask >> arity != 0 || chunks.contains_key(&mask) {
+                return Err(invalid(format!("Bad or duplicate chunk {:#x}", mask)));
+            }
+            let bytes = Bitset::bytes_for(arity);
+            let mut chunk = Bitset::try_of(arity).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::OutOfMemory,
+                               format!("Cannot allocate {} bytes", bytes))
+            })?;
+            let mut result = Ok(());
+            chunk.fill_words(|words| {
+                for word in words {
+                    match r.u64() {
+                        Ok(w) => *word = w,
+                        Err(e) => {
+                            result = Err(e);
+                            return;
+                        }
+                    }
+                }
+            });
+            result?;
+            chunks.insert(mask, chunk);
+        }
+        let expected = r.hash;
+        if u64::from_le_bytes(r.take()?) != expected {
+            return Err(invalid("Checksum mismatch".to_string()));
+        }
+        if r.inner.read(&mut [0])? != 0 {
+            return Err(invalid("Trailing data after checkpoint".to_string()));
+        }
+        Ok(Checkpoint {
+            arity,
+            rank,
+            stats,
+            chunks,
+        })
+    }
+
+    /// Writes to a temporary file next to `path` first, so that a crash
+    /// while saving does not destroy the previous checkpoint.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temp = OsString::from(path.as_os_str());
+        temp.push(".tmp");
+        {
+            let file = File::create(&temp)?;
+            let mut writer = BufWriter::new(file);
+            self.write_to(&mut writer)?;
+            writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        }
+        fs::rename(&temp, path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Checkpoint> {
+        Checkpoint::read_from(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+fn test_checkpoints(arity: u32) -> Vec<Vec<u8>> {
+    let mut saved = Vec::new();
+    let sample = |v: u32| -> Result<bool, io::Error> { Ok(v % 5 != 1) };
+    ::try_generate_checkpointed(&sample, &mut |_, _, _| {}, arity, &mut |checkpoint| {
+        let mut bytes = Vec::new();
+        checkpoint.write_to(&mut bytes)?;
+        saved.push(bytes);
+        Ok(())
+    }).unwrap();
+    saved
+}
+
+#[test]
+fn test_checkpoint_roundtrip() {
+    for bytes in test_checkpoints(7) {
+        let checkpoint = Checkpoint::read_from(&mut &bytes[..]).unwrap();
+        let mut again = Vec::new();
+        checkpoint.write_to(&mut again).unwrap();
+        assert!(bytes == again);
+    }
+}
+
+#[test]
+fn test_checkpoint_file() {
+    let path = ::std::env::temp_dir().join(format!("implicants-{}.ckpt", ::std::process::id()));
+    let bytes = &test_checkpoints(5)[2];
+    Checkpoint::read_from(&mut &bytes[..]).unwrap().save(&path).unwrap();
+    let loaded = Checkpoint::load(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(2, loaded.unwrap().rank());
+}
+
+#[test]
+fn test_checkpoint_damaged() {
+    let bytes = &test_checkpoints(4)[1];
+    let checkpoint = Checkpoint::read_from(&mut &bytes[..]).unwrap();
+    assert_eq!((4, 1), (checkpoint.arity(), checkpoint.rank()));
+
+    for i in 0..bytes.len() {
+        let mut damaged = bytes.clone();
+        damaged[i] ^= 0x10;
+        let err = Checkpoint::read_from(&mut &damaged[..]).err().expect("damage went unnoticed");
+        // A damaged chunk count can also make it run out of data.
+        assert!(err.kind() == io::ErrorKind::InvalidData ||
+                err.kind() == io::ErrorKind::UnexpectedEof);
+    }
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert!(Checkpoint::read_from(&mut &longer[..]).is_err());
+    assert!(Checkpoint::read_from(&mut &bytes[..bytes.len() - 1]).is_err());
+
+    let mut future = bytes.clone();
+    future[8] = 2;
+    let err = Checkpoint::read_from(&mut &future[..]).err().unwrap();
+    assert_eq!("Unsupported checkpoint version 2", err.to_string());
+}
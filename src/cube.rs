@@ -0,0 +1,241 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cube calculus on implicants and covers, as used by two-level logic
+//! minimizers.  Cubes never have literals beyond the arity of their cover,
+//! so the operations on single cubes don't need to know it.
+
+use implicant::{low_mask, Cover, Implicant};
+#[cfg(test)]
+use masked_count;
+use prelude::*;
+#[cfg(test)]
+use test_random::Rng;
+use unate::WideCover;
+
+impl Implicant {
+    /// Positions where this cube has a literal.
+    fn literal_mask(&self) -> u32 {
+        !self.mask_gap
+    }
+
+    /// Number of variables in which the two cubes have opposite literals.
+    /// The cubes intersect iff this is 0.
+    pub fn distance(&self, other: &Implicant) -> u32 {
+        self.conflicts(other).count_ones()
+    }
+
+    fn conflicts(&self, other: &Implicant) -> u32 {
+        (self.value ^ other.value) & self.literal_mask() & other.literal_mask()
+    }
+
+    /// The inputs that lie in both cubes, if any.
+    pub fn intersect(&self, other: &Implicant) -> Option<Implicant> {
+        if self.conflicts(other) != 0 {
+            return None;
+        }
+        Some(Implicant::new(self.mask_gap & other.mask_gap, self.value | other.value))
+    }
+
+    /// The largest cube that lies in the union of both cubes, and touches
+    /// both.  Only exists if the distance is at most 1.
+    pub fn consensus(&self, other: &Implicant) -> Option<Implicant> {
+        let conflicts = self.conflicts(other);
+        if conflicts.count_ones() > 1 {
+            return None;
+        }
+        Some(Implicant::new((self.mask_gap & other.mask_gap) | conflicts,
+                            (self.value | other.value) & !conflicts))
+    }
+
+    /// The inputs of `self` that are not in `other`, as a set of cubes that
+    /// are as large as possible, but may overlap.
+    pub fn sharp(&self, other: &Implicant) -> Vec<Implicant> {
+        if self.conflicts(other) != 0 {
+            return vec![*self];
+        }
+        bits_of(self.mask_gap & other.literal_mask())
+            .map(|bit| Implicant::new(self.mask_gap & !bit, self.value | (!other.value & bit)))
+            .collect()
+    }
+
+    /// Like `sharp`, but the cubes are pairwise disjoint.
+    pub fn disjoint_sharp(&self, other: &Implicant) -> Vec<Implicant> {
+        if self.conflicts(other) != 0 {
+            return vec![*self];
+        }
+        let mut fixed = *self;
+        let mut result = Vec::new();
+        for bit in bits_of(self.mask_gap & other.literal_mask()) {
+            let mask_gap = fixed.mask_gap & !bit;
+            result.push(Implicant::new(mask_gap, fixed.value | (!other.value & bit)));
+            fixed = Implicant::new(mask_gap, fixed.value | (other.value & bit));
+        }
+        result
+    }
+}
+
+/// The set bits of `mask`, one at a time, lowest first.
+fn bits_of(mut mask: u32) -> impl Iterator<Item = u32> {
+    (0..mask.count_ones()).map(move |_| {
+        let bit = mask & mask.wrapping_neg();
+        mask &= !bit;
+        bit
+    })
+}
+
+impl Cover {
+    /// The cover restricted to the inputs in `cube`, as a function of the
+    /// remaining variables: the literals of `cube` become gaps everywhere.
+    pub fn cofactor(&self, cube: &Implicant) -> Cover {
+        let fixed = low_mask(self.arity) & cube.literal_mask();
+        let implicants = self.implicants
+            .iter()
+            .filter(|imp| imp.conflicts(cube) == 0)
+            .map(|imp| Implicant::new(imp.mask_gap | fixed, imp.value & !fixed))
+            .collect();
+        Cover::new(self.arity, implicants)
+    }
+
+    /// Cofactor with respect to the single literal `var = value`.
+    pub fn cofactor_literal(&self, var: u32, value: bool) -> Cover {
+        assert!(var < self.arity, "Variable {} out of range", var);
+        let bit = 1 << var;
+        let cube = Implicant::new(low_mask(self.arity) & !bit, if value { bit } else { 0 });
+        self.cofactor(&cube)
+    }
+
+    /// Removes every implicant that is covered by another one, keeping
+    /// the first of any duplicates.  Does not change the function.
+    pub fn remove_contained(&mut self) {
+        let implicants = &self.implicants;
+        let keep: Vec<bool> = (0..implicants.len())
+            .map(|i| {
+                !implicants.iter().enumerate().any(|(j, other)| {
+                    j != i && other.covers(&implicants[i]) &&
+                        (other != &implicants[i] || j < i)
+                })
+            })
+            .collect();
+        let mut keep = keep.into_iter();
+        self.implicants.retain(|_| keep.next().unwrap());
+    }
+
+    /// A cover of exactly the inputs that this cover does not contain.
+    /// See `unate::WideCover::complement`.
+    pub fn complement(&self) -> Cover {
+        WideCover::from(self).complement().to_cover().unwrap()
+    }
+}
+
+#[cfg(test)]
+fn test_all_points(arity: u32) -> masked_count::UpIter {
+    masked_count::up(low_mask(arity))
+}
+
+#[test]
+fn test_cube_pairs() {
+    let mut rng = Rng::new(1);
+    for _ in 0..300 {
+        let pair = rng.cover(5, 2);
+        let (a, b) = (pair[0], pair[1]);
+        let points = |cubes: &[Implicant]| -> Vec<u32> {
+            test_all_points(5).filter(|&p| cubes.iter().any(|c| c.contains(p))).collect()
+        };
+
+        let both: Vec<u32> = test_all_points(5).filter(|&p| a.contains(p) && b.contains(p)).collect();
+        assert_eq!(both, points(&a.intersect(&b).into_iter().collect::<Vec<_>>()));
+        assert_eq!(both.is_empty(), a.distance(&b) != 0);
+
+        let minus: Vec<u32> = test_all_points(5).filter(|&p| a.contains(p) && !b.contains(p)).collect();
+        let sharp = a.sharp(&b);
+        assert_eq!(minus, points(&sharp));
+        let disjoint = a.disjoint_sharp(&b);
+        assert_eq!(minus, points(&disjoint));
+        let sizes: usize = disjoint.iter().map(|c| c.points().count()).sum();
+        assert_eq!(minus.len(), sizes);
+
+        match a.consensus(&b) {
+            None => assert!(a.distance(&b) > 1),
+            Some(c) => {
+                assert!(c.points().all(|p| a.contains(p) || b.contains(p)));
+                assert!(c.intersect(&a).is_some() && c.intersect(&b).is_some());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_consensus_textbook() {
+    // x0 x1' and x1 x2 yield x0 x2.
+    let a = Implicant::from_cube_string("10-").unwrap();
+    let b = Implicant::from_cube_string("-11").unwrap();
+    assert_eq!(Implicant::from_cube_string("1-1"), a.consensus(&b));
+    assert_eq!(None, a.consensus(&Implicant::from_cube_string("01-").unwrap()));
+}
+
+#[test]
+fn test_cofactor() {
+    let mut rng = Rng::new(2);
+    for _ in 0..100 {
+        let cover = rng.cover(5, 4);
+        let cube = rng.cover(5, 1)[0];
+        let cofactor = cover.cofactor(&cube);
+        let fixed = low_mask(5) & !cube.mask_gap;
+        assert!(cofactor.iter().all(|imp| imp.mask_gap & fixed == fixed));
+        for p in test_all_points(5) {
+            assert_eq!(cover.contains((p & !fixed) | cube.value), cofactor.contains(p));
+        }
+    }
+    let cover = Cover::new(2, vec![Implicant::from_cube_string("1-").unwrap()]);
+    assert_eq!(vec![Implicant::new(0b11, 0)], cover.cofactor_literal(0, true).implicants);
+    assert!(cover.cofactor_literal(0, false).is_empty());
+}
+
+#[test]
+fn test_remove_contained() {
+    let mut rng = Rng::new(3);
+    for _ in 0..100 {
+        let cover = rng.cover(4, 6);
+        let mut reduced = cover.clone();
+        reduced.remove_contained();
+        assert!(test_all_points(4).all(|p| cover.contains(p) == reduced.contains(p)));
+        for (i, a) in reduced.iter().enumerate() {
+            assert!(reduced.iter().enumerate().all(|(j, b)| i == j || !b.covers(a)));
+        }
+    }
+    let one = Implicant::from_cube_string("1-").unwrap();
+    let mut cover = Cover::new(2, vec![one, Implicant::new(0, 0b11), one]);
+    cover.remove_contained();
+    assert_eq!(vec![one], cover.implicants);
+}
+
+#[test]
+fn test_complement() {
+    let mut rng = Rng::new(4);
+    for arity in 0..7 {
+        for len in 0..6 {
+            let cover = rng.cover(arity, len);
+            let complement = cover.complement();
+            assert_eq!(arity, complement.arity);
+            assert!(test_all_points(arity).all(|p| cover.contains(p) != complement.contains(p)),
+                    "{:?} vs {:?}", cover, complement);
+        }
+    }
+    let all = Cover::new(3, vec![Implicant::new(0b111, 0)]);
+    assert!(all.complement().is_empty());
+    assert_eq!(all, Cover::new(3, vec![]).complement());
+}
@@ -0,0 +1,301 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Turns a set of implicants into a sum-of-products (or exclusive-or
+//! sum-of-products) expression in the syntax of some target language.
+
+use esop::Esop;
+use implicant::{low_mask, Cover, Implicant};
+use prelude::*;
+use variables::Variables;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    /// Textbook notation, like `a·b' + c`.
+    Text,
+    Rust,
+    C,
+    Verilog,
+    Vhdl,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operators {
+    /// Short-circuit operators (`&&`, `||`, `!`), or `·`, `+`, `'` in text.
+    Logical,
+    /// Bitwise operators (`&`, `|`, and `!` or `~`), as text as well.
+    /// VHDL only knows `and`, `or`, `not`, and ignores this.
+    Bitwise,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parens {
+    /// Only where the language requires them (VHDL does, when mixing
+    /// `and` and `or`).
+    Minimal,
+    /// Around every product of more than one literal.
+    Products,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub operators: Operators,
+    pub parens: Parens,
+    /// Break lines after an "or" if they would get longer than this.
+    /// Continuation lines are indented by four spaces.
+    pub line_width: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            operators: Operators::Logical,
+            parens: Parens::Minimal,
+            line_width: None,
+        }
+    }
+}
+
+struct Syntax {
+    and: &'static str,
+    or: &'static str,
+    not_prefix: &'static str,
+    not_suffix: &'static str,
+    truth: &'static str,
+    falsity: &'static str,
+    needs_parens: bool,
+}
+
+fn syntax_of(language: Language, operators: Operators) -> Syntax {
+    use self::Language::*;
+    use self::Operators::*;
+    let (and, or, not_prefix, not_suffix) = match (language, operators) {
+        (Text, Logical) => ("·", " + ", "", "'"),
+        (Text, Bitwise) => (" & ", " | ", "~", ""),
+        (Rust, Logical) | (C, Logical) | (Verilog, Logical) => (" && ", " || ", "!", ""),
+        (Rust, Bitwise) | (C, Bitwise) => (" & ", " | ", "!", ""),
+        (Verilog, Bitwise) => (" & ", " | ", "~", ""),
+        (Vhdl, _) => (" and ", " or ", "not ", ""),
+    };
+    let (truth, falsity) = match language {
+        Text | C => ("1", "0"),
+        Rust => ("true", "false"),
+        Verilog => ("1'b1", "1'b0"),
+        Vhdl => ("'1'", "'0'"),
+    };
+    Syntax {
+        and,
+        or,
+        not_prefix,
+        not_suffix,
+        truth,
+        falsity,
+        needs_parens: language == Vhdl,
+    }
+}
+
+/// Like `emit`, but takes the names from `variables`, and inverts the
+/// literals of active-low variables.
+pub fn emit_named(cover: &Cover, variables: &Variables, language: Language, options: &Options)
+                  -> String {
+    let signals = variables.cover_to_signals(cover);
+    emit(&signals, &variables.names(), language, options)
+}
+
+/// Renders the disjunction of `cover` in the given language.
+/// The variable at bit `i` is called `names[i]`, so `names` also
+/// determines the arity.  Literals appear in order of their bit position,
+/// products in the order given.
+pub fn emit(cover: &[Implicant], names: &[&str], language: Language, options: &Options) -> String {
+    let arity = names.len() as u32;
+    let syntax = syntax_of(language, options.operators);
+    for imp in cover {
+        assert_eq!(0, imp.value & !low_mask(arity),
+                   "Implicant {:?} uses more than {} variables", imp, arity);
+    }
+
+    if cover.is_empty() {
+        return syntax.falsity.to_string();
+    }
+    if cover.iter().any(|imp| imp.literals(arity) == 0) {
+        return syntax.truth.to_string();
+    }
+
+    let wrap = cover.len() > 1 && (syntax.needs_parens || options.parens == Parens::Products);
+    let terms: Vec<String> = cover.iter()
+        .map(|imp| product(imp, names, &syntax, wrap))
+        .collect();
+    join_terms(&terms, syntax.or, options)
+}
+
+/// Renders the exclusive or of `esop`, like `emit` does for a cover.
+/// Except in text, "and" binds tighter than "exclusive or" only among
+/// the bitwise operators, so with logical operators every product gets
+/// parentheses.  A cube without literals becomes the constant `1`.
+pub fn emit_esop(esop: &Esop, names: &[&str], language: Language, options: &Options) -> String {
+    use self::Language::*;
+    assert_eq!(esop.arity, names.len() as u32,
+               "ESOP is for {} variables, but got {} names",
+               esop.arity, names.len());
+    let syntax = syntax_of(language, options.operators);
+    if esop.is_empty() {
+        return syntax.falsity.to_string();
+    }
+    let xor = match (language, options.operators) {
+        (Text, Operators::Logical) => " ⊕ ",
+        (Vhdl, _) => " xor ",
+        _ => " ^ ",
+    };
+    let needs_parens = language == Vhdl ||
+        (language != Text && options.operators == Operators::Logical);
+    let wrap = esop.len() > 1 && (needs_parens || options.parens == Parens::Products);
+    let terms: Vec<String> = esop.iter()
+        .map(|imp| product(imp, names, &syntax, wrap))
+        .collect();
+    join_terms(&terms, xor, options)
+}
+
+/// One product, in parentheses if `wrap` and there is more than one literal.
+fn product(imp: &Implicant, names: &[&str], syntax: &Syntax, wrap: bool) -> String {
+    let literals: Vec<String> = (0..names.len() as u32)
+        .filter(|i| imp.mask_gap & (1 << i) == 0)
+        .map(|i| if imp.value & (1 << i) != 0 {
+            names[i as usize].to_string()
+        } else {
+            format!("{}{}{}", syntax.not_prefix, names[i as usize], syntax.not_suffix)
+        })
+        .collect();
+    if literals.is_empty() {
+        return syntax.truth.to_string();
+    }
+    let product = literals.join(syntax.and);
+    if wrap && literals.len() > 1 {
+        format!("({})", product)
+    } else {
+        product
+    }
+}
+
+fn join_terms(terms: &[String], separator: &str, options: &Options) -> String {
+    match options.line_width {
+        None => terms.join(separator),
+        Some(width) => wrap_terms(terms, separator, width),
+    }
+}
+
+fn wrap_terms(terms: &[String], separator: &str, width: usize) -> String {
+    let mut out = terms[0].clone();
+    let mut line_len = terms[0].chars().count();
+    for term in &terms[1..] {
+        let term_len = term.chars().count();
+        if line_len + separator.chars().count() + term_len > width {
+            out.push_str(separator.trim_end());
+            out.push_str("\n    ");
+            line_len = 4;
+        } else {
+            out.push_str(separator);
+            line_len += separator.chars().count();
+        }
+        out.push_str(term);
+        line_len += term_len;
+    }
+    out
+}
+
+#[cfg(test)]
+fn test_cover() -> Vec<Implicant> {
+    // a·b' + c
+    vec![Implicant::new(0b100, 0b001), Implicant::new(0b011, 0b100)]
+}
+
+#[test]
+fn test_emit_languages() {
+    let names = ["a", "b", "c"];
+    let opts = Options::default();
+    let cover = test_cover();
+    assert_eq!("a·b' + c", emit(&cover, &names, Language::Text, &opts));
+    assert_eq!("a && !b || c", emit(&cover, &names, Language::Rust, &opts));
+    assert_eq!("a && !b || c", emit(&cover, &names, Language::C, &opts));
+    assert_eq!("a && !b || c", emit(&cover, &names, Language::Verilog, &opts));
+    assert_eq!("(a and not b) or c", emit(&cover, &names, Language::Vhdl, &opts));
+
+    let opts = Options {
+        operators: Operators::Bitwise,
+        parens: Parens::Products,
+        line_width: None,
+    };
+    assert_eq!("(a & ~b) | c", emit(&cover, &names, Language::Text, &opts));
+    assert_eq!("(a & !b) | c", emit(&cover, &names, Language::Rust, &opts));
+    assert_eq!("(a & ~b) | c", emit(&cover, &names, Language::Verilog, &opts));
+}
+
+#[test]
+fn test_emit_constants() {
+    let names = ["a", "b"];
+    let opts = Options::default();
+    assert_eq!("false", emit(&[], &names, Language::Rust, &opts));
+    assert_eq!("1'b0", emit(&[], &names, Language::Verilog, &opts));
+    let taut = [Implicant::new(0b01, 0b10), Implicant::new(0b11, 0)];
+    assert_eq!("1", emit(&taut, &names, Language::C, &opts));
+    assert_eq!("'1'", emit(&taut, &names, Language::Vhdl, &opts));
+    // A single product never needs parentheses:
+    let single = [Implicant::new(0, 0b01)];
+    assert_eq!("a and not b", emit(&single, &names, Language::Vhdl, &opts));
+}
+
+#[test]
+fn test_emit_wrap() {
+    let names = ["alpha", "beta", "gamma"];
+    let opts = Options {
+        line_width: Some(20),
+        ..Options::default()
+    };
+    let cover = vec![Implicant::new(0b100, 0b001),
+                     Implicant::new(0b011, 0b100),
+                     Implicant::new(0b101, 0b010)];
+    assert_eq!("alpha && !beta ||\n    gamma || beta",
+               emit(&cover, &names, Language::Rust, &opts));
+}
+
+#[test]
+fn test_emit_named() {
+    use variables::Variable;
+    let vars = Variables::new(vec![Variable::new("req", 0), Variable::active_low("rst_n", 1)])
+        .unwrap();
+    // req asserted and reset asserted, or reset not asserted.
+    let cover = Cover::new(2, vec![Implicant::new(0, 0b11), Implicant::new(0b01, 0)]);
+    assert_eq!("req && !rst_n || rst_n",
+               emit_named(&cover, &vars, Language::Rust, &Options::default()));
+}
+
+#[test]
+fn test_emit_esop() {
+    let names = ["a", "b", "c"];
+    let opts = Options::default();
+    // a·b' ⊕ c ⊕ 1
+    let esop = Esop::new(3, vec![Implicant::new(0b100, 0b001),
+                                 Implicant::new(0b011, 0b100),
+                                 Implicant::new(0b111, 0)]);
+    assert_eq!("a·b' ⊕ c ⊕ 1", emit_esop(&esop, &names, Language::Text, &opts));
+    assert_eq!("(a && !b) ^ c ^ true", emit_esop(&esop, &names, Language::Rust, &opts));
+    assert_eq!("(a and not b) xor c xor '1'", emit_esop(&esop, &names, Language::Vhdl, &opts));
+    let opts = Options {
+        operators: Operators::Bitwise,
+        ..Options::default()
+    };
+    assert_eq!("a & ~b ^ c ^ 1'b1", emit_esop(&esop, &names, Language::Verilog, &opts));
+    assert_eq!("0", emit_esop(&Esop::new(3, vec![]), &names, Language::C, &opts));
+}
@@ -0,0 +1,65 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use core::convert::Infallible;
+use core::error;
+use core::fmt;
+use variables::VariablesError;
+
+/// Why `try_generate` gave up.  `E` is the error type of the sampler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error<E = Infallible> {
+    /// Only arities up to 31 are supported.
+    UnsupportedArity(u32),
+    /// Allocating a single chunk of this many bytes failed.
+    AllocationFailed { bytes: usize },
+    /// The chunks would have needed more than `limit` bytes at once.
+    MemoryLimitExceeded { limit: usize, required: usize },
+    /// The sampler returned an error.  No further samples were taken,
+    /// and nothing was reported.
+    User(E),
+    /// Saving a checkpoint failed.  The run stopped right afterwards.
+    Checkpoint(E),
+    /// The variables don't fit the arity.  Nothing was sampled.
+    Variables(VariablesError),
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::UnsupportedArity(arity) => {
+                write!(f, "Can only handle at most 31 bits, but tried {} bits", arity)
+            }
+            Error::AllocationFailed { bytes } => write!(f, "Cannot allocate {} bytes", bytes),
+            Error::MemoryLimitExceeded { limit, required } => {
+                write!(f, "Need {} bytes, but the limit is {} bytes", required, limit)
+            }
+            Error::User(ref e) => write!(f, "Sampling failed: {}", e),
+            Error::Checkpoint(ref e) => write!(f, "Checkpoint failed: {}", e),
+            Error::Variables(ref e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: error::Error + 'static> error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::User(ref e) | Error::Checkpoint(ref e) => Some(e),
+            Error::Variables(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,319 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Exclusive-or sum of products: the function is `1` wherever an odd
+//! number of cubes contain the input.  For parity-heavy logic this is
+//! often much smaller than any sum of products.
+//!
+//! The minimizer is heuristic, in the spirit of EXORCISM: Cubes that are
+//! equal cancel, cubes that differ in one position merge into one, and
+//! cubes that differ in two positions are rewritten into a different
+//! pair ("exorlink") whenever that lets the cover shrink further.
+
+use core::ops::Deref;
+#[cfg(test)]
+use implicant::low_mask;
+use implicant::{Cover, Implicant};
+use prelude::*;
+#[cfg(test)]
+use test_random::Rng;
+
+/// A set of implicants over a known number of variables, interpreted
+/// as their exclusive or.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Esop {
+    pub arity: u32,
+    pub implicants: Vec<Implicant>,
+}
+
+impl Deref for Esop {
+    type Target = [Implicant];
+
+    fn deref(&self) -> &[Implicant] {
+        &self.implicants
+    }
+}
+
+impl Esop {
+    pub fn new(arity: u32, implicants: Vec<Implicant>) -> Self {
+        Esop { arity, implicants }
+    }
+
+    /// One cube per input where `sampling_fn` holds, with adjacent
+    /// minterms already merged.
+    pub fn from_minterms(sampling_fn: &dyn Fn(u32) -> bool, arity: u32) -> Self {
+        assert!(arity < 32,
+                "Can only handle at most 31 bits, but tried {} bits",
+                arity);
+        let mut cubes = Vec::new();
+        for v in 0..(1u32 << arity) {
+            if sampling_fn(v) {
+                insert(&mut cubes, Implicant::new(0, v));
+            }
+        }
+        Esop::new(arity, cubes)
+    }
+
+    /// The same function as `cover`, e.g. the primes that `generate`
+    /// reports.  The cubes are first made disjoint, so that "or" and
+    /// "exclusive or" agree.
+    pub fn from_cover(cover: &Cover) -> Self {
+        let mut cubes = Vec::new();
+        for (i, imp) in cover.iter().enumerate() {
+            let mut pieces = vec![*imp];
+            for earlier in &cover[..i] {
+                pieces = pieces.iter().flat_map(|p| p.disjoint_sharp(earlier)).collect();
+            }
+            for piece in pieces {
+                insert(&mut cubes, piece);
+            }
+        }
+        Esop::new(cover.arity, cubes)
+    }
+
+    /// Whether an odd number of cubes contain the given input.
+    pub fn contains(&self, point: u32) -> bool {
+        self.implicants.iter().filter(|imp| imp.contains(point)).count() % 2 == 1
+    }
+
+    /// Total number of literals over all cubes.
+    pub fn literals(&self) -> u32 {
+        self.implicants.iter().map(|imp| imp.literals(self.arity)).sum()
+    }
+
+    /// Applies the cube transformations until neither the number of cubes
+    /// nor the number of literals goes down any more.  The function stays
+    /// the same.  See `minimize` for the running time.
+    pub fn improve(&mut self) {
+        let mut cubes = Vec::new();
+        for imp in &self.implicants {
+            insert(&mut cubes, *imp);
+        }
+        let mut current = cost(&cubes, self.arity);
+        let mut best = (current, cubes.clone());
+        // Moves that keep the number of cubes are allowed, but only this
+        // many, so that the search always ends.
+        let mut sideways = 4 * cubes.len() + 16;
+        let mut rng = 0x2545_f491u32;
+        loop {
+            let mut improving = None;
+            let mut neutral = Vec::new();
+            for (i, j, pair) in exorlinks(&cubes) {
+                // Any merge or cancellation removes a cube.  Otherwise, the
+                // candidate has as many cubes, and only the literals change.
+                let merges = difference(&pair[0], &pair[1]).count_ones() <= 1 ||
+                    pair.iter().any(|imp| has_partner(&cubes, i, j, imp));
+                let before = cubes[i].literals(self.arity) + cubes[j].literals(self.arity);
+                let after = pair[0].literals(self.arity) + pair[1].literals(self.arity);
+                if merges || after < before {
+                    improving = Some((i, j, pair));
+                    break;
+                }
+                neutral.push((i, j, pair));
+            }
+            let (i, j, pair) = match improving {
+                Some(link) => link,
+                None if sideways > 0 && !neutral.is_empty() => {
+                    sideways -= 1;
+                    rng = rng.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                    neutral.swap_remove((rng >> 16) as usize % neutral.len())
+                }
+                None => break,
+            };
+            cubes.swap_remove(j);
+            cubes.swap_remove(i);
+            insert(&mut cubes, pair[0]);
+            insert(&mut cubes, pair[1]);
+            current = cost(&cubes, self.arity);
+            if current < best.0 {
+                best = (current, cubes.clone());
+            }
+        }
+        let mut best = best.1;
+        best.sort();
+        self.implicants = best;
+    }
+}
+
+/// A small ESOP of `sampling_fn`, starting from its minterms.
+/// Note that `sampling_fn` is called once for each input.
+///
+/// With `n` cubes, each pass of `Esop::improve` tries all pairs and checks
+/// each candidate against all cubes, so it takes time proportional to `n³`.
+/// Each pass removes a cube or a literal, except for at most `4·n + 16`.
+/// As there can be up to `2^arity` minterms, this is only practical for
+/// functions with few inputs or few minterms.
+pub fn minimize(sampling_fn: &dyn Fn(u32) -> bool, arity: u32) -> Esop {
+    let mut esop = Esop::from_minterms(sampling_fn, arity);
+    esop.improve();
+    esop
+}
+
+/// Number of cubes first, then number of literals.
+fn cost(cubes: &[Implicant], arity: u32) -> (usize, u32) {
+    (cubes.len(), cubes.iter().map(|imp| imp.literals(arity)).sum())
+}
+
+/// Positions where the cubes differ, including literal versus gap.
+fn difference(a: &Implicant, b: &Implicant) -> u32 {
+    (a.mask_gap ^ b.mask_gap) | (a.value ^ b.value)
+}
+
+/// The literal at `bit`: `None` for a gap.
+fn literal(imp: &Implicant, bit: u32) -> Option<bool> {
+    if imp.mask_gap & bit != 0 {
+        None
+    } else {
+        Some(imp.value & bit != 0)
+    }
+}
+
+fn with_literal(imp: &Implicant, bit: u32, literal: Option<bool>) -> Implicant {
+    match literal {
+        None => Implicant::new(imp.mask_gap | bit, imp.value & !bit),
+        Some(false) => Implicant::new(imp.mask_gap & !bit, imp.value & !bit),
+        Some(true) => Implicant::new(imp.mask_gap & !bit, imp.value | bit),
+    }
+}
+
+/// The exclusive or of two different literals of one variable, which is
+/// again a literal: `x ⊕ x' = 1`, `x ⊕ 1 = x'`, and `x' ⊕ 1 = x`.
+fn xor_literals(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(_), Some(_)) => None,
+        (Some(x), None) | (None, Some(x)) => Some(!x),
+        (None, None) => unreachable!("equal literals"),
+    }
+}
+
+fn xor_at(a: &Implicant, b: &Implicant, bit: u32) -> Option<bool> {
+    xor_literals(literal(a, bit), literal(b, bit))
+}
+
+/// Adds `imp` to a list of cubes in which no two are at distance 0 or 1,
+/// cancelling and merging as necessary to keep it that way.
+fn insert(cubes: &mut Vec<Implicant>, mut imp: Implicant) {
+    loop {
+        let partner = cubes.iter()
+            .position(|other| difference(&imp, other).count_ones() <= 1);
+        let other = match partner {
+            Some(index) => cubes.swap_remove(index),
+            None => {
+                cubes.push(imp);
+                return;
+            }
+        };
+        let diff = difference(&imp, &other);
+        if diff == 0 {
+            return;
+        }
+        imp = with_literal(&imp, diff, xor_at(&imp, &other, diff));
+    }
+}
+
+/// Whether `imp` is at distance 0 or 1 from any cube but `cubes[i]` and `cubes[j]`.
+fn has_partner(cubes: &[Implicant], i: usize, j: usize, imp: &Implicant) -> bool {
+    cubes.iter()
+        .enumerate()
+        .any(|(k, other)| k != i && k != j && difference(imp, other).count_ones() <= 1)
+}
+
+/// For each pair of cubes at distance 2, both pairs that have the same
+/// exclusive or.  With `C` the common part, `C·a·b ⊕ C·c·d` equals both
+/// `C·(a⊕c)·b ⊕ C·c·(b⊕d)` and `C·a·(b⊕d) ⊕ C·(a⊕c)·d`.
+/// Yields `(i, j, pair)` with `i < j`.
+fn exorlinks(cubes: &[Implicant]) -> Vec<(usize, usize, [Implicant; 2])> {
+    let mut result = Vec::new();
+    for (j, b) in cubes.iter().enumerate() {
+        for (i, a) in cubes[..j].iter().enumerate() {
+            let diff = difference(a, b);
+            if diff.count_ones() != 2 {
+                continue;
+            }
+            let first = diff & diff.wrapping_neg();
+            let second = diff & !first;
+            for &(x, y) in &[(first, second), (second, first)] {
+                let c = with_literal(a, x, xor_at(a, b, x));
+                let d = with_literal(b, y, xor_at(a, b, y));
+                result.push((i, j, [c, d]));
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+fn test_check(esop: &Esop, f: &dyn Fn(u32) -> bool) {
+    for v in 0..(1u32 << esop.arity) {
+        assert_eq!(f(v), esop.contains(v), "at {:b} in {:?}", v, esop);
+    }
+    for imp in esop.iter() {
+        assert_eq!(0, imp.value & !low_mask(esop.arity));
+    }
+}
+
+#[test]
+fn test_parity() {
+    // Any sum of products needs all 16 minterms.
+    let f = |v: u32| v.count_ones() % 2 == 1;
+    let esop = minimize(&f, 4);
+    test_check(&esop, &f);
+    assert_eq!(4, esop.len());
+    assert_eq!(4, esop.literals());
+}
+
+#[test]
+fn test_exorlink() {
+    // a·b ⊕ a'·b' only gets cheaper through an exorlink, into b ⊕ a'.
+    let f = |v: u32| v == 0b00 || v == 0b11;
+    let mut esop = Esop::new(2, vec![Implicant::new(0, 0b00), Implicant::new(0, 0b11)]);
+    esop.improve();
+    test_check(&esop, &f);
+    assert_eq!(2, esop.len());
+    assert_eq!(2, esop.literals());
+}
+
+#[test]
+fn test_constants() {
+    assert!(minimize(&|_| false, 3).is_empty());
+    assert_eq!(vec![Implicant::new(0b111, 0)], minimize(&|_| true, 3).implicants);
+}
+
+#[test]
+fn test_random_functions() {
+    let mut rng = Rng::new(0x1234_5678);
+    for arity in 1..7 {
+        for _ in 0..8 {
+            let table = rng.table();
+            let f = |v: u32| (table >> v) & 1 == 1;
+            let minterms = (0..(1u32 << arity)).filter(|&v| f(v)).count();
+            let esop = minimize(&f, arity);
+            test_check(&esop, &f);
+            assert!(esop.len() <= minterms);
+        }
+    }
+}
+
+#[test]
+fn test_from_cover() {
+    // a + b, overlapping in a·b
+    let cover = Cover::new(2, vec![Implicant::new(0b10, 0b01), Implicant::new(0b01, 0b10)]);
+    let mut esop = Esop::from_cover(&cover);
+    test_check(&esop, &|v| cover.contains(v));
+    esop.improve();
+    test_check(&esop, &|v| cover.contains(v));
+    assert_eq!(2, esop.len());
+}
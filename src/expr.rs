@@ -0,0 +1,442 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A tiny language for boolean expressions, as an alternative to writing
+//! a sampling closure by hand.
+//!
+//! From loosest to tightest binding: `->` (right-associative), `|`, `^`,
+//! `&`, `!`.  Constants are `0`, `1`, `false` and `true`.  Variables are
+//! identifiers, and get assigned to bits in order of first appearance.
+//! Parentheses, `!` and `->` nest at most 256 levels deep.
+
+use core::error;
+use core::fmt;
+use prelude::*;
+use variables::Variables;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset into the source.
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at {}: {}", self.position, self.message)
+    }
+}
+
+impl error::Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Const(bool),
+    Var(u32),
+    Not,
+    And,
+    Or,
+    Xor,
+    Implies,
+}
+
+/// A compiled expression.  Evaluation runs a short postfix program on a
+/// stack that (usually) lives in a single register.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expression {
+    ops: Vec<Op>,
+    names: Vec<String>,
+    depth: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Const(bool),
+    Not,
+    And,
+    Or,
+    Xor,
+    Implies,
+    Open,
+    Close,
+    End,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    ops: Vec<Op>,
+    names: Vec<String>,
+    /// If given, only these variables may appear.
+    declared: Option<&'a Variables>,
+    /// How many `(`, `!` and `->` the parser is currently inside of.
+    nesting: usize,
+}
+
+/// Deeper nesting is rejected, instead of overflowing the stack of the
+/// recursive descent on untrusted input.
+const MAX_NESTING: usize = 256;
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: &str) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.pos,
+            message: message.to_string(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.source[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Returns the next token and its length, without consuming it.
+    fn peek(&mut self) -> Result<(Token<'a>, usize), ParseError> {
+        self.skip_whitespace();
+        let rest = &self.source[self.pos..];
+        let c = match rest.chars().next() {
+            None => return Ok((Token::End, 0)),
+            Some(c) => c,
+        };
+        let token = match c {
+            '!' => (Token::Not, 1),
+            '&' => (Token::And, 1),
+            '|' => (Token::Or, 1),
+            '^' => (Token::Xor, 1),
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            '-' if rest.starts_with("->") => (Token::Implies, 2),
+            '0' => (Token::Const(false), 1),
+            '1' => (Token::Const(true), 1),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                match &rest[..len] {
+                    "true" => (Token::Const(true), len),
+                    "false" => (Token::Const(false), len),
+                    ident => (Token::Ident(ident), len),
+                }
+            }
+            _ => return self.error(&format!("unexpected character '{}'", c)),
+        };
+        Ok(token)
+    }
+
+    /// Runs `f` one level deeper, failing if that is too deep.
+    fn nested<F>(&mut self, f: F) -> Result<(), ParseError>
+        where F: FnOnce(&mut Self) -> Result<(), ParseError>
+    {
+        if self.nesting >= MAX_NESTING {
+            return self.error("expression is nested too deeply");
+        }
+        self.nesting += 1;
+        f(self)?;
+        self.nesting -= 1;
+        Ok(())
+    }
+
+    fn accept(&mut self, expected: Token) -> Result<bool, ParseError> {
+        let (token, len) = self.peek()?;
+        if token == expected {
+            self.pos += len;
+        }
+        Ok(token == expected)
+    }
+
+    fn implication(&mut self) -> Result<(), ParseError> {
+        self.binary(Token::Or)?;
+        if self.accept(Token::Implies)? {
+            self.nested(Self::implication)?;
+            self.ops.push(Op::Implies);
+        }
+        Ok(())
+    }
+
+    /// Parses a left-associative chain of `level`, or anything tighter.
+    fn binary(&mut self, level: Token) -> Result<(), ParseError> {
+        let (tighter, op) = match level {
+            Token::Or => (Some(Token::Xor), Op::Or),
+            Token::Xor => (Some(Token::And), Op::Xor),
+            Token::And => (None, Op::And),
+            _ => unreachable!(),
+        };
+        let operand = |parser: &mut Self| match tighter {
+            Some(t) => parser.binary(t),
+            None => parser.unary(),
+        };
+        operand(self)?;
+        while self.accept(level)? {
+            operand(self)?;
+            self.ops.push(op);
+        }
+        Ok(())
+    }
+
+    fn unary(&mut self) -> Result<(), ParseError> {
+        if self.accept(Token::Not)? {
+            self.nested(Self::unary)?;
+            self.ops.push(Op::Not);
+            return Ok(());
+        }
+        let (token, len) = self.peek()?;
+        match token {
+            Token::Const(b) => self.ops.push(Op::Const(b)),
+            Token::Ident(name) if self.declared.is_some() => {
+                let var = match self.declared.unwrap().find(name) {
+                    Some(var) => var,
+                    None => return self.error(&format!("unknown variable '{}'", name)),
+                };
+                self.ops.push(Op::Var(var.bit));
+                if var.active_low {
+                    self.ops.push(Op::Not);
+                }
+            }
+            Token::Ident(name) => {
+                let index = match self.names.iter().position(|n| n == name) {
+                    Some(index) => index,
+                    None => {
+                        if self.names.len() >= 31 {
+                            return self.error("can only handle at most 31 variables");
+                        }
+                        self.names.push(name.to_string());
+                        self.names.len() - 1
+                    }
+                };
+                self.ops.push(Op::Var(index as u32));
+            }
+            Token::Open => {
+                self.pos += len;
+                self.nested(Self::implication)?;
+                if !self.accept(Token::Close)? {
+                    return self.error("expected ')'");
+                }
+                return Ok(());
+            }
+            Token::End => return self.error("unexpected end of expression"),
+            _ => return self.error("expected a variable, constant, '!' or '('"),
+        }
+        self.pos += len;
+        Ok(())
+    }
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Expression, ParseError> {
+        Expression::parse_with(source, None)
+    }
+
+    /// Like `parse`, but the variables and their bits are given, and
+    /// names refer to signals: an active-low `rst_n` is true where its
+    /// bit is 0.
+    pub fn parse_named(source: &str, variables: &Variables) -> Result<Expression, ParseError> {
+        Expression::parse_with(source, Some(variables))
+    }
+
+    fn parse_with(source: &str, declared: Option<&Variables>) -> Result<Expression, ParseError> {
+        let mut parser = Parser {
+            source,
+            pos: 0,
+            ops: Vec::new(),
+            names: declared.map_or_else(Vec::new, |vars| {
+                vars.names().iter().map(|name| name.to_string()).collect()
+            }),
+            declared,
+            nesting: 0,
+        };
+        parser.implication()?;
+        if parser.peek()?.0 != Token::End {
+            return parser.error("expected an operator or end of expression");
+        }
+
+        let mut depth = 0;
+        let mut max_depth = 0;
+        for op in &parser.ops {
+            match *op {
+                Op::Const(_) | Op::Var(_) => depth += 1,
+                Op::Not => {}
+                _ => depth -= 1,
+            }
+            max_depth = max_depth.max(depth);
+        }
+        Ok(Expression {
+            ops: parser.ops,
+            names: parser.names,
+            depth: max_depth,
+        })
+    }
+
+    /// Number of distinct variables, i.e., the arity to pass to `generate`.
+    pub fn arity(&self) -> u32 {
+        self.names.len() as u32
+    }
+
+    /// Variable names, in bit order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The variables that `parse` assigned, all active-high.
+    pub fn variables(&self) -> Variables {
+        Variables::from_names(&self.names).unwrap()
+    }
+
+    /// Evaluates the expression, where variable `i` has the value of bit `i`.
+    /// This is meant to be used as `sampling_fn`.
+    pub fn eval(&self, v: u32) -> bool {
+        if self.depth <= 64 {
+            let mut stack = 0u64;
+            for op in &self.ops {
+                let top = stack & 1 == 1;
+                let pop = |stack: &mut u64| {
+                    *stack >>= 1;
+                    *stack & 1 == 1
+                };
+                let result = match *op {
+                    Op::Const(b) => {
+                        stack <<= 1;
+                        b
+                    }
+                    Op::Var(i) => {
+                        stack <<= 1;
+                        (v >> i) & 1 == 1
+                    }
+                    Op::Not => !top,
+                    Op::And => pop(&mut stack) & top,
+                    Op::Or => pop(&mut stack) | top,
+                    Op::Xor => pop(&mut stack) ^ top,
+                    Op::Implies => !pop(&mut stack) | top,
+                };
+                stack = (stack & !1) | result as u64;
+            }
+            stack & 1 == 1
+        } else {
+            let mut stack: Vec<bool> = Vec::with_capacity(self.depth);
+            for op in &self.ops {
+                let result = match *op {
+                    Op::Const(b) => b,
+                    Op::Var(i) => (v >> i) & 1 == 1,
+                    Op::Not => !stack.pop().unwrap(),
+                    _ => {
+                        let rhs = stack.pop().unwrap();
+                        let lhs = stack.pop().unwrap();
+                        match *op {
+                            Op::And => lhs & rhs,
+                            Op::Or => lhs | rhs,
+                            Op::Xor => lhs ^ rhs,
+                            _ => !lhs | rhs,
+                        }
+                    }
+                };
+                stack.push(result);
+            }
+            stack[0]
+        }
+    }
+
+    /// Runs `generate` on this expression.
+    pub fn generate(&self, report_fn: &mut dyn FnMut(u32, u32, bool)) {
+        ::generate(&|v| self.eval(v), report_fn, self.arity());
+    }
+}
+
+#[cfg(test)]
+fn test_agrees(source: &str, f: &dyn Fn(u32) -> bool) {
+    let expr = Expression::parse(source).unwrap();
+    for v in 0..(1 << expr.arity()) {
+        assert_eq!(f(v), expr.eval(v), "{} at {:b}", source, v);
+    }
+}
+
+#[test]
+fn test_precedence() {
+    let bit = |v: u32, i: u32| (v >> i) & 1 == 1;
+    test_agrees("a | b & c", &|v| bit(v, 0) | (bit(v, 1) & bit(v, 2)));
+    test_agrees("a ^ b & c", &|v| bit(v, 0) ^ (bit(v, 1) & bit(v, 2)));
+    test_agrees("a | b ^ c", &|v| bit(v, 0) | (bit(v, 1) ^ bit(v, 2)));
+    test_agrees("!a & b", &|v| !bit(v, 0) & bit(v, 1));
+    test_agrees("!(a & b)", &|v| !(bit(v, 0) & bit(v, 1)));
+    test_agrees("a -> b -> c", &|v| !bit(v, 0) | !bit(v, 1) | bit(v, 2));
+    test_agrees("(a -> b) -> c", &|v| !(!bit(v, 0) | bit(v, 1)) | bit(v, 2));
+    test_agrees("a | b -> c", &|v| !(bit(v, 0) | bit(v, 1)) | bit(v, 2));
+    test_agrees("x_1 & !!x_1 | false", &|v| bit(v, 0));
+    test_agrees("1 ^ a", &|v| !bit(v, 0));
+}
+
+#[test]
+fn test_deep() {
+    // Needs more than 64 stack entries.
+    let source = format!("{}a{}", "1 & (".repeat(70), ")".repeat(70));
+    test_agrees(&source, &|v| v & 1 == 1);
+}
+
+#[test]
+fn test_names() {
+    let expr = Expression::parse("req_valid & !busy | req_valid & force").unwrap();
+    assert_eq!(3, expr.arity());
+    assert_eq!(&["req_valid", "busy", "force"], expr.names());
+    assert!(expr.eval(0b001));
+    assert!(!expr.eval(0b011));
+    assert!(expr.eval(0b111));
+    assert!(!expr.eval(0b110));
+}
+
+#[test]
+fn test_parse_named() {
+    use variables::Variable;
+    let vars = Variables::new(vec![Variable::active_low("rst_n", 0),
+                                   Variable::new("en", 1),
+                                   Variable::new("unused", 2)])
+        .unwrap();
+    let expr = Expression::parse_named("en & !rst_n", &vars).unwrap();
+    assert_eq!(3, expr.arity());
+    // True when enabled and the signal is low, i.e. reset is asserted.
+    assert!(expr.eval(0b011));
+    assert!(!expr.eval(0b010));
+    assert!(!expr.eval(0b001));
+    assert!(Expression::parse_named("rst_n", &vars).unwrap().eval(0b000));
+    let err = Expression::parse_named("en & bogus", &vars).unwrap_err();
+    assert_eq!(5, err.position);
+    assert_eq!("unknown variable 'bogus'", err.message);
+
+    let plain = Expression::parse("b | a").unwrap();
+    assert_eq!(vec!["b", "a"], plain.variables().names());
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(3, Expression::parse("a &").unwrap_err().position);
+    assert_eq!(2, Expression::parse("a $ b").unwrap_err().position);
+    assert_eq!(3, Expression::parse("(a b").unwrap_err().position);
+    assert!(Expression::parse("").is_err());
+    assert!(Expression::parse("a - b").is_err());
+    assert!(Expression::parse("a)").is_err());
+}
+
+#[test]
+fn test_nesting_limit() {
+    let deep = |open: &str, close: &str, n: usize| {
+        let mut source = open.repeat(n);
+        source.push('a');
+        source.push_str(&close.repeat(n));
+        source
+    };
+    assert!(Expression::parse(&deep("(", ")", MAX_NESTING)).is_ok());
+    for &(open, close) in &[("(", ")"), ("!", ""), ("a -> ", "")] {
+        let err = Expression::parse(&deep(open, close, 200_000)).unwrap_err();
+        assert_eq!("expression is nested too deeply", err.message);
+    }
+}
@@ -0,0 +1,225 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Static-1 hazards of sum-of-products covers.
+//!
+//! When the inputs change from `from` to `to`, and the function is `1`
+//! on both, the output may still glitch to `0` in between, unless a single
+//! cube of the cover contains the whole transition cube, i.e., `from`,
+//! `to` and everything "between" them.  Minimum covers usually omit the
+//! consensus terms that would guarantee this.
+
+use core::error;
+use core::fmt;
+use implicant::{Cover, Implicant};
+use minimize::{select_cover_of_cubes, CostModel};
+use prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    /// Some cube contains the whole transition cube.
+    HazardFree,
+    /// The function is `1` on the whole transition cube, but no single
+    /// cube contains it.  Adding a cube fixes this.
+    LogicHazard,
+    /// The function itself is `0` somewhere on the transition cube,
+    /// so no cover can make this transition glitch-free.
+    FunctionHazard,
+}
+
+/// A requested transition that can't be made hazard-free.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FunctionHazard {
+    pub from: u32,
+    pub to: u32,
+}
+
+impl fmt::Display for FunctionHazard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Transition from {:b} to {:b} has a function hazard", self.from, self.to)
+    }
+}
+
+impl error::Error for FunctionHazard {}
+
+/// The smallest cube that contains both inputs.
+pub fn transition_cube(from: u32, to: u32) -> Implicant {
+    let changing = from ^ to;
+    Implicant::new(changing, from & !changing)
+}
+
+/// Checks the transition between two inputs inside the cover.
+pub fn check_transition(cover: &Cover, from: u32, to: u32) -> Transition {
+    assert!(cover.contains(from) && cover.contains(to),
+            "Not a static-1 transition: {:b} to {:b}", from, to);
+    let cube = transition_cube(from, to);
+    if cover.iter().any(|imp| imp.covers(&cube)) {
+        Transition::HazardFree
+    } else if cube.points().all(|p| cover.contains(p)) {
+        Transition::LogicHazard
+    } else {
+        Transition::FunctionHazard
+    }
+}
+
+/// All single-input changes between inputs of the cover that no single
+/// cube holds steady, as `(from, to)` with `from < to`, in ascending order.
+pub fn static_1_hazards(cover: &Cover) -> Vec<(u32, u32)> {
+    adjacent_pairs(cover.arity, &|v| cover.contains(v))
+        .into_iter()
+        .filter(|&(from, to)| check_transition(cover, from, to) == Transition::LogicHazard)
+        .collect()
+}
+
+fn adjacent_pairs(arity: u32, on_fn: &dyn Fn(u32) -> bool) -> Vec<(u32, u32)> {
+    let mut pairs = Vec::new();
+    for from in 0..(1u32 << arity) {
+        if !on_fn(from) {
+            continue;
+        }
+        for i in 0..arity {
+            let to = from | (1 << i);
+            if to != from && on_fn(to) {
+                pairs.push((from, to));
+            }
+        }
+    }
+    pairs
+}
+
+fn primes_of(on_fn: &dyn Fn(u32) -> bool, arity: u32) -> Vec<Implicant> {
+    let mut primes = Vec::new();
+    {
+        let mut collect = |mask_gap: u32, value: u32, is_prime: bool| {
+            if is_prime {
+                primes.push(Implicant::new(mask_gap, value));
+            }
+        };
+        ::generate(on_fn, &mut collect, arity);
+    }
+    primes
+}
+
+/// Adds cubes to `cover` until all single-input changes and the given
+/// (possibly multi-input) `transitions` are free of static-1 hazards.
+/// Each added cube is a prime with as few literals as possible.
+pub fn augment(cover: &Cover, transitions: &[(u32, u32)]) -> Result<Cover, FunctionHazard> {
+    let mut result = cover.clone();
+    let mut pending = static_1_hazards(cover);
+    for &(from, to) in transitions {
+        match check_transition(cover, from, to) {
+            Transition::HazardFree => {}
+            Transition::LogicHazard => pending.push((from, to)),
+            Transition::FunctionHazard => return Err(FunctionHazard { from, to }),
+        }
+    }
+    if pending.is_empty() {
+        return Ok(result);
+    }
+
+    let primes = primes_of(&|v| cover.contains(v), cover.arity);
+    for (from, to) in pending {
+        let cube = transition_cube(from, to);
+        if result.iter().any(|imp| imp.covers(&cube)) {
+            // An earlier addition took care of it.
+            continue;
+        }
+        let best = primes.iter()
+            .filter(|prime| prime.covers(&cube))
+            .min_by_key(|prime| prime.literals(cover.arity))
+            .expect("Some prime contains every cube of the function");
+        result.implicants.push(*best);
+    }
+    Ok(result)
+}
+
+/// Like `minimize::minimize_with_dc`, but the result is a cheapest cover
+/// without static-1 hazards for all single-input changes (between inputs
+/// where `on_fn` holds and `dc_fn` doesn't) and the given `transitions`.
+/// For the latter, `on_fn` or `dc_fn` must hold on the whole transition cube.
+pub fn hazard_free_cover(on_fn: &dyn Fn(u32) -> bool,
+                         dc_fn: &dyn Fn(u32) -> bool,
+                         arity: u32,
+                         transitions: &[(u32, u32)],
+                         costs: &CostModel)
+                         -> Result<Cover, FunctionHazard> {
+    assert_eq!(arity, costs.arity(),
+               "Cost model is for {} variables, but function has {}",
+               costs.arity(), arity);
+    let care_on = |v| on_fn(v) && !dc_fn(v);
+    let mut required: Vec<Implicant> = (0..(1u32 << arity))
+        .filter(|&v| care_on(v))
+        .map(|v| Implicant::new(0, v))
+        .collect();
+    for (from, to) in adjacent_pairs(arity, &care_on) {
+        required.push(transition_cube(from, to));
+    }
+    for &(from, to) in transitions {
+        let cube = transition_cube(from, to);
+        if !cube.points().all(|v| on_fn(v) || dc_fn(v)) {
+            return Err(FunctionHazard { from, to });
+        }
+        required.push(cube);
+    }
+
+    let primes = primes_of(&|v| on_fn(v) || dc_fn(v), arity);
+    let chosen = select_cover_of_cubes(&primes, &required, costs)
+        .expect("Primes must contain every required cube");
+    Ok(Cover::new(arity, chosen))
+}
+
+#[test]
+fn test_consensus() {
+    // f = x0 x1' + x1 x2, the minimum cover lacks the consensus x0 x2.
+    let cover = Cover::new(3, vec![Implicant::new(0b001, 0b110), Implicant::new(0b100, 0b001)]);
+    assert_eq!(vec![(0b101, 0b111)], static_1_hazards(&cover));
+    assert_eq!(Transition::LogicHazard, check_transition(&cover, 0b111, 0b101));
+    assert_eq!(Transition::HazardFree, check_transition(&cover, 0b110, 0b111));
+
+    let consensus = Implicant::new(0b010, 0b101);
+    let fixed = augment(&cover, &[]).unwrap();
+    assert_eq!(vec![cover[0], cover[1], consensus], fixed.implicants);
+    assert!(static_1_hazards(&fixed).is_empty());
+
+    let f = |v: u32| ((v & 0b011) == 0b001) || ((v & 0b110) == 0b110);
+    let selected = hazard_free_cover(&f, &|_| false, 3, &[], &CostModel::terms(3)).unwrap();
+    let mut expected = fixed.implicants.clone();
+    expected.sort();
+    assert_eq!(expected, selected.implicants);
+}
+
+#[test]
+fn test_multi_input() {
+    // Constant true, but covered by x0 + x0'.
+    let cover = Cover::new(3, vec![Implicant::new(0b110, 0b001), Implicant::new(0b110, 0b000)]);
+    assert_eq!(vec![(0b000, 0b001), (0b010, 0b011), (0b100, 0b101), (0b110, 0b111)],
+               static_1_hazards(&cover));
+    assert_eq!(Transition::LogicHazard, check_transition(&cover, 0b000, 0b111));
+    let fixed = augment(&cover, &[(0b000, 0b111)]).unwrap();
+    assert_eq!(Implicant::new(0b111, 0), fixed[2]);
+    assert_eq!(3, fixed.len());
+
+    // x0 + x1: going from 01 to 10 may pass 00.
+    let or = Cover::new(2, vec![Implicant::new(0b10, 0b01), Implicant::new(0b01, 0b10)]);
+    assert_eq!(Transition::FunctionHazard, check_transition(&or, 0b01, 0b10));
+    assert_eq!(Err(FunctionHazard { from: 0b01, to: 0b10 }), augment(&or, &[(0b01, 0b10)]));
+    let f = |v: u32| v != 0;
+    assert_eq!(Err(FunctionHazard { from: 0b01, to: 0b10 }),
+               hazard_free_cover(&f, &|_| false, 2, &[(0b01, 0b10)], &CostModel::terms(2)));
+    // With a don't-care at 00, the transition is fine after all.
+    let cover = hazard_free_cover(&f, &|v| v == 0, 2, &[(0b01, 0b10)], &CostModel::terms(2)).unwrap();
+    assert_eq!(vec![Implicant::new(0b11, 0)], cover.implicants);
+}
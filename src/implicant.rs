@@ -0,0 +1,203 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! First-class representation of what `generate` reports.
+
+#[cfg(feature = "serde")]
+use core::convert::TryFrom;
+use core::ops::Deref;
+use masked_count;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use prelude::*;
+
+/// A single implicant, exactly as passed to the report callback:
+/// `M` is `1` in `mask_gap` and `0` in `value`, whereas `0` and `1`
+/// are `0` in `mask_gap` and stored verbatim in `value`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Masks"))]
+pub struct Implicant {
+    pub mask_gap: u32,
+    pub value: u32,
+}
+
+/// What an `Implicant` deserializes from, before `Implicant::new`'s check.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct Masks {
+    mask_gap: u32,
+    value: u32,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Masks> for Implicant {
+    type Error = String;
+
+    fn try_from(masks: Masks) -> Result<Self, String> {
+        if masks.mask_gap & masks.value != 0 {
+            return Err(format!("Gaps {:b} and value {:b} overlap",
+                               masks.mask_gap, masks.value));
+        }
+        Ok(Implicant::new(masks.mask_gap, masks.value))
+    }
+}
+
+impl Implicant {
+    pub fn new(mask_gap: u32, value: u32) -> Self {
+        assert_eq!(0, mask_gap & value,
+                   "Gaps {:b} and value {:b} overlap", mask_gap, value);
+        Implicant { mask_gap, value }
+    }
+
+    /// Whether the given input lies inside this implicant.
+    pub fn contains(&self, point: u32) -> bool {
+        (point & !self.mask_gap) == self.value
+    }
+
+    /// Whether every input of `other` lies inside this implicant.
+    pub fn covers(&self, other: &Implicant) -> bool {
+        (other.mask_gap & !self.mask_gap) == 0 &&
+            (other.value & !self.mask_gap) == self.value
+    }
+
+    /// Number of literals, i.e., non-gap positions among the lowest `arity` bits.
+    pub fn literals(&self, arity: u32) -> u32 {
+        (low_mask(arity) & !self.mask_gap).count_ones()
+    }
+
+    /// Cube notation like in PLA files, e.g. `1-0`.  Character `i` stands
+    /// for bit `i`, so the least significant bit comes first.
+    pub fn to_cube_string(&self, arity: u32) -> String {
+        (0..arity)
+            .map(|i| if self.mask_gap & (1 << i) != 0 {
+                '-'
+            } else if self.value & (1 << i) != 0 {
+                '1'
+            } else {
+                '0'
+            })
+            .collect()
+    }
+
+    /// Inverse of `to_cube_string`, where the length is the arity.
+    /// Returns `None` on characters other than `0`, `1` and `-`.
+    pub fn from_cube_string(cube: &str) -> Option<Implicant> {
+        if cube.len() > 32 {
+            return None;
+        }
+        let mut imp = Implicant::new(0, 0);
+        for (i, c) in cube.chars().enumerate() {
+            match c {
+                '0' => {}
+                '1' => imp.value |= 1 << i,
+                '-' => imp.mask_gap |= 1 << i,
+                _ => return None,
+            }
+        }
+        Some(imp)
+    }
+
+    /// All inputs that lie inside this implicant, in ascending order.
+    pub fn points(&self) -> Points {
+        Points {
+            inner: masked_count::up(self.mask_gap),
+            value: self.value,
+        }
+    }
+}
+
+/// Iterator over all inputs of an `Implicant`.
+pub struct Points {
+    inner: masked_count::UpIter,
+    value: u32,
+}
+
+impl Iterator for Points {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.inner.next().map(|sub| sub | self.value)
+    }
+}
+
+/// A set of implicants over a known number of variables, interpreted
+/// as their disjunction (sum of products).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cover {
+    pub arity: u32,
+    pub implicants: Vec<Implicant>,
+}
+
+impl Cover {
+    pub fn new(arity: u32, implicants: Vec<Implicant>) -> Self {
+        Cover { arity, implicants }
+    }
+
+    /// Whether the given input is inside any implicant of the cover.
+    pub fn contains(&self, point: u32) -> bool {
+        self.implicants.iter().any(|imp| imp.contains(point))
+    }
+}
+
+impl Deref for Cover {
+    type Target = [Implicant];
+
+    fn deref(&self) -> &[Implicant] {
+        &self.implicants
+    }
+}
+
+/// The lowest `arity` bits set.  Also works for `arity == 32`.
+pub fn low_mask(arity: u32) -> u32 {
+    assert!(arity <= 32, "Can only handle at most 32 bits, but tried {} bits", arity);
+    if arity == 32 { !0 } else { (1 << arity) - 1 }
+}
+
+/// Like `low_mask`, for up to 64 bits.
+pub fn wide_mask(arity: u32) -> u64 {
+    assert!(arity <= 64, "Can only handle at most 64 bits, but tried {} bits", arity);
+    if arity == 64 { !0 } else { (1 << arity) - 1 }
+}
+
+#[test]
+fn test_contains() {
+    // 1M0
+    let imp = Implicant::new(0b010, 0b100);
+    assert!(imp.contains(0b100));
+    assert!(imp.contains(0b110));
+    assert!(!imp.contains(0b000));
+    assert!(!imp.contains(0b101));
+    assert_eq!(vec![0b100, 0b110], imp.points().collect::<Vec<_>>());
+    assert_eq!(2, imp.literals(3));
+    assert_eq!(3, imp.literals(4));
+    assert_eq!("0-1", imp.to_cube_string(3));
+    assert_eq!("0-10", imp.to_cube_string(4));
+    assert_eq!(Some(imp), Implicant::from_cube_string("0-1"));
+    assert_eq!(Some(imp), Implicant::from_cube_string("0-10"));
+    assert_eq!(None, Implicant::from_cube_string("0-x"));
+}
+
+#[test]
+fn test_covers() {
+    let big = Implicant::new(0b011, 0b100);
+    assert!(big.covers(&Implicant::new(0b010, 0b101)));
+    assert!(big.covers(&Implicant::new(0, 0b111)));
+    assert!(big.covers(&big));
+    assert!(!big.covers(&Implicant::new(0b110, 0b001)));
+    assert!(!big.covers(&Implicant::new(0, 0b011)));
+}
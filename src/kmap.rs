@@ -0,0 +1,406 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Karnaugh maps of functions with up to 6 variables, as text or SVG,
+//! optionally with implicants drawn on top.
+//!
+//! The lower half of the variables (rounded up) runs along the columns,
+//! the rest along the rows, both in Gray code order.  Axis labels list
+//! the variables' values in bit order, like cube strings do.
+
+use core::fmt::Write;
+use emit::{emit, Language, Options};
+use implicant::Implicant;
+use prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Zero,
+    One,
+    DontCare,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Only `+`, `-` and `|` for the grid.
+    Ascii,
+    /// Box-drawing characters.
+    Unicode,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KMap {
+    names: Vec<String>,
+    cells: Vec<Cell>,
+}
+
+const CELL: usize = 40;
+const PALETTE: [&str; 8] = [
+    "#e41a1c", "#377eb8", "#4daf4a", "#984ea3", "#ff7f00", "#a65628", "#f781bf", "#999999",
+];
+
+fn gray(i: u32) -> u32 {
+    i ^ (i >> 1)
+}
+
+fn inverse_gray(mut g: u32) -> u32 {
+    let mut i = 0;
+    while g != 0 {
+        i ^= g;
+        g >>= 1;
+    }
+    i
+}
+
+/// The lowest `count` bits, in bit order.
+fn bit_string(bits: u32, count: u32) -> String {
+    (0..count).map(|i| if bits & (1 << i) != 0 { '1' } else { '0' }).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn center(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    let left = (width.saturating_sub(len)) / 2;
+    let right = width.saturating_sub(len + left);
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+/// Maximal runs `[start, end)` of covered positions.  The flag says
+/// whether the run continues on the other side of the map.
+fn runs(covered: &[bool]) -> Vec<(usize, usize, bool)> {
+    let n = covered.len();
+    let wraps = covered[0] && covered[n - 1] && covered.iter().any(|&c| !c);
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < n {
+        if !covered[i] {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < n && covered[i] {
+            i += 1;
+        }
+        result.push((start, i, wraps && (start == 0 || i == n)));
+    }
+    result
+}
+
+impl KMap {
+    /// The variable at bit `i` is called `names[i]`, so `names` also
+    /// determines the arity.  `dc_fn` takes precedence over `on_fn`.
+    pub fn new(on_fn: &dyn Fn(u32) -> bool, dc_fn: &dyn Fn(u32) -> bool, names: &[&str]) -> KMap {
+        assert!(names.len() <= 6,
+                "Can only draw at most 6 variables, but tried {}",
+                names.len());
+        let cells = (0..1 << names.len())
+            .map(|v| if dc_fn(v) {
+                Cell::DontCare
+            } else if on_fn(v) {
+                Cell::One
+            } else {
+                Cell::Zero
+            })
+            .collect();
+        KMap {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            cells,
+        }
+    }
+
+    pub fn arity(&self) -> u32 {
+        self.names.len() as u32
+    }
+
+    pub fn cell(&self, point: u32) -> Cell {
+        self.cells[point as usize]
+    }
+
+    fn col_vars(&self) -> u32 {
+        self.arity().div_ceil(2)
+    }
+
+    fn row_vars(&self) -> u32 {
+        self.arity() - self.col_vars()
+    }
+
+    pub fn rows(&self) -> usize {
+        1 << self.row_vars()
+    }
+
+    pub fn cols(&self) -> usize {
+        1 << self.col_vars()
+    }
+
+    /// The input shown at the given row and column.
+    pub fn point(&self, row: usize, col: usize) -> u32 {
+        gray(col as u32) | (gray(row as u32) << self.col_vars())
+    }
+
+    /// Row and column of the given input.
+    pub fn position(&self, point: u32) -> (usize, usize) {
+        let col_mask = (1 << self.col_vars()) - 1;
+        (inverse_gray(point >> self.col_vars()) as usize, inverse_gray(point & col_mask) as usize)
+    }
+
+    fn label(&self, from: u32, count: u32) -> String {
+        let names = &self.names[from as usize..(from + count) as usize];
+        let separator = if names.iter().all(|name| name.chars().count() == 1) { "" } else { "," };
+        names.join(separator)
+    }
+
+    fn corner(&self) -> String {
+        if self.arity() == 0 {
+            return String::new();
+        }
+        format!("{}\\{}", self.label(self.col_vars(), self.row_vars()), self.label(0, self.col_vars()))
+    }
+
+    fn row_label(&self, row: usize) -> String {
+        bit_string(gray(row as u32), self.row_vars())
+    }
+
+    fn col_label(&self, col: usize) -> String {
+        bit_string(gray(col as u32), self.col_vars())
+    }
+
+    /// Which rows and columns of the map the implicant touches.
+    fn covered(&self, imp: &Implicant) -> (Vec<bool>, Vec<bool>) {
+        let rows = (0..self.rows())
+            .map(|row| (0..self.cols()).any(|col| imp.contains(self.point(row, col))))
+            .collect();
+        let cols = (0..self.cols())
+            .map(|col| (0..self.rows()).any(|row| imp.contains(self.point(row, col))))
+            .collect();
+        (rows, cols)
+    }
+
+    fn legend(&self, imp: &Implicant) -> String {
+        let names: Vec<&str> = self.names.iter().map(String::as_str).collect();
+        format!("{}  {}",
+                imp.to_cube_string(self.arity()),
+                emit(&[*imp], &names, Language::Text, &Options::default()))
+    }
+
+    /// Each cell shows `0`, `1` or `x` (don't care), followed by the
+    /// letters of the implicants that contain it.  A legend follows the map.
+    pub fn to_text(&self, implicants: &[Implicant], style: Style) -> String {
+        assert!(implicants.len() <= 26, "Can only label 26 implicants");
+        let (h, v, corners) = match style {
+            Style::Ascii => ("-", "|", ["+", "+", "+", "+", "+", "+", "+", "+", "+"]),
+            Style::Unicode => ("─", "│", ["┌", "┬", "┐", "├", "┼", "┤", "└", "┴", "┘"]),
+        };
+        let contents: Vec<Vec<String>> = (0..self.rows())
+            .map(|row| (0..self.cols()).map(|col| {
+                let point = self.point(row, col);
+                let mut text = match self.cell(point) {
+                    Cell::Zero => "0",
+                    Cell::One => "1",
+                    Cell::DontCare => "x",
+                }.to_string();
+                let letters: String = implicants.iter().enumerate()
+                    .filter(|&(_, imp)| imp.contains(point))
+                    .map(|(i, _)| (b'A' + i as u8) as char)
+                    .collect();
+                if !letters.is_empty() {
+                    text.push(' ');
+                    text.push_str(&letters);
+                }
+                text
+            }).collect())
+            .collect();
+        let width = contents.iter().flat_map(|row| row.iter())
+            .map(String::len)
+            .max()
+            .unwrap()
+            .max(self.col_vars() as usize);
+        let corner = self.corner();
+        let margin = corner.chars().count().max(self.row_vars() as usize);
+
+        let rule = |left: &str, middle: &str, right: &str| {
+            let segments: Vec<String> = (0..self.cols()).map(|_| h.repeat(width + 2)).collect();
+            format!("{} {}{}{}\n", " ".repeat(margin), left, segments.join(middle), right)
+        };
+        let mut out = String::new();
+        let labels: Vec<String> = (0..self.cols())
+            .map(|col| center(&self.col_label(col), width + 2))
+            .collect();
+        out.push_str(format!("{:>m$}  {}", corner, labels.join(" "), m = margin).trim_end());
+        out.push('\n');
+        out.push_str(&rule(corners[0], corners[1], corners[2]));
+        for (row, cells) in contents.iter().enumerate() {
+            if row > 0 {
+                out.push_str(&rule(corners[3], corners[4], corners[5]));
+            }
+            let cells: Vec<String> = cells.iter().map(|text| format!(" {:w$} ", text, w = width)).collect();
+            let label = self.row_label(row);
+            out.push_str(&format!("{}{} {}{}{}\n",
+                                  " ".repeat(margin - label.len()),
+                                  label,
+                                  v,
+                                  cells.join(v),
+                                  v));
+        }
+        out.push_str(&rule(corners[6], corners[7], corners[8]));
+        for (i, imp) in implicants.iter().enumerate() {
+            out.push_str(&format!("{}: {}\n", (b'A' + i as u8) as char, self.legend(imp)));
+        }
+        out
+    }
+
+    /// A standalone SVG document.  Implicants become translucent rounded
+    /// rectangles, which leave the map on one side and come back on the
+    /// other if they wrap around.  Hovering shows the implicant.
+    pub fn to_svg(&self, implicants: &[Implicant]) -> String {
+        let left = 20 + 10 * self.corner().chars().count().max(self.row_vars() as usize);
+        let top = 40;
+        let grid_w = self.cols() * CELL;
+        let grid_h = self.rows() * CELL;
+        let width = left + grid_w + 20;
+        let height = top + grid_h + 20 + 20 * implicants.len();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+                               viewBox=\"0 0 {} {}\" font-family=\"monospace\" font-size=\"14\">",
+                         width, height, width, height);
+        let _ = writeln!(out, "<defs><clipPath id=\"kmap-grid\"><rect x=\"{}\" y=\"{}\" width=\"{}\" \
+                               height=\"{}\"/></clipPath></defs>",
+                         left, top, grid_w, grid_h);
+        let _ = writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+                         left - 4, top - 8, escape(&self.corner()));
+        for col in 0..self.cols() {
+            let _ = writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                             left + col * CELL + CELL / 2, top - 8, self.col_label(col));
+        }
+        for row in 0..self.rows() {
+            let y = top + row * CELL;
+            let _ = writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+                             left - 6, y + CELL / 2 + 5, self.row_label(row));
+            for col in 0..self.cols() {
+                let x = left + col * CELL;
+                let (text, colour) = match self.cell(self.point(row, col)) {
+                    Cell::Zero => ("0", "black"),
+                    Cell::One => ("1", "black"),
+                    Cell::DontCare => ("x", "gray"),
+                };
+                let _ = writeln!(out, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" \
+                                       fill=\"none\" stroke=\"black\"/>",
+                                 x, y, CELL, CELL);
+                let _ = writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"{}\">{}</text>",
+                                 x + CELL / 2, y + CELL / 2 + 5, colour, text);
+            }
+        }
+
+        for (i, imp) in implicants.iter().enumerate() {
+            let colour = PALETTE[i % PALETTE.len()];
+            // Nested implicants shouldn't hide each other's outline.
+            let inset = 4 + 3 * (i % 4);
+            let (rows, cols) = self.covered(imp);
+            let _ = writeln!(out, "<g clip-path=\"url(#kmap-grid)\" fill=\"{}\" fill-opacity=\"0.15\" \
+                                   stroke=\"{}\" stroke-width=\"2\">",
+                             colour, colour);
+            let _ = writeln!(out, "<title>{}</title>", escape(&self.legend(imp)));
+            for &(row_start, row_end, row_wraps) in &runs(&rows) {
+                for &(col_start, col_end, col_wraps) in &runs(&cols) {
+                    let mut x = left + col_start * CELL + inset;
+                    let mut w = (col_end - col_start) * CELL - 2 * inset;
+                    let mut y = top + row_start * CELL + inset;
+                    let mut h = (row_end - row_start) * CELL - 2 * inset;
+                    // Reach beyond the edge, so the clipped side stays open.
+                    if col_wraps {
+                        if col_start == 0 {
+                            x -= CELL;
+                        }
+                        w += CELL;
+                    }
+                    if row_wraps {
+                        if row_start == 0 {
+                            y -= CELL;
+                        }
+                        h += CELL;
+                    }
+                    let _ = writeln!(out, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"8\"/>",
+                                     x, y, w, h);
+                }
+            }
+            let _ = writeln!(out, "</g>");
+            let _ = writeln!(out, "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>",
+                             left, top + grid_h + 20 + 20 * i, colour, escape(&self.legend(imp)));
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+#[test]
+fn test_positions() {
+    let names = ["a", "b", "c", "d", "e"];
+    let map = KMap::new(&|v| v % 3 == 0, &|_| false, &names);
+    assert_eq!((4, 8), (map.rows(), map.cols()));
+    for point in 0..32 {
+        let (row, col) = map.position(point);
+        assert_eq!(point, map.point(row, col));
+    }
+    // Neighbours differ in exactly one bit, also across the edges.
+    for row in 0..4 {
+        for col in 0..8 {
+            let point = map.point(row, col);
+            assert_eq!(1, (point ^ map.point(row, (col + 1) % 8)).count_ones());
+            assert_eq!(1, (point ^ map.point((row + 1) % 4, col)).count_ones());
+        }
+    }
+}
+
+#[test]
+fn test_text() {
+    // a + b', with a don't-care at a·b
+    let names = ["a", "b"];
+    let map = KMap::new(&|v| v != 0b10, &|v| v == 0b11, &names);
+    let cover = [Implicant::new(0b10, 0b01), Implicant::new(0b01, 0b00)];
+    assert_eq!("b\\a    0      1\n\
+                \x20   ┌──────┬──────┐\n\
+                \x20 0 │ 1 B  │ 1 AB │\n\
+                \x20   ├──────┼──────┤\n\
+                \x20 1 │ 0    │ x A  │\n\
+                \x20   └──────┴──────┘\n\
+                A: 1-  a\n\
+                B: -0  b'\n",
+               map.to_text(&cover, Style::Unicode));
+    assert_eq!("b\\a   0   1\n\
+                \x20   +---+---+\n\
+                \x20 0 | 1 | 1 |\n\
+                \x20   +---+---+\n\
+                \x20 1 | 0 | x |\n\
+                \x20   +---+---+\n",
+               map.to_text(&[], Style::Ascii));
+}
+
+#[test]
+fn test_svg_wraps() {
+    // a' on a 2x4 map: columns 0 and 3, so it wraps.
+    let names = ["a", "b", "c"];
+    let map = KMap::new(&|v| v & 1 == 0, &|_| false, &names);
+    let svg = map.to_svg(&[Implicant::new(0b110, 0b000)]);
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.ends_with("</svg>\n"));
+    // Two pieces, each reaching one cell beyond the map.
+    assert!(svg.contains("<rect x=\"24\" y=\"44\" width=\"72\" height=\"72\" rx=\"8\"/>"), "{}", svg);
+    assert!(svg.contains("<rect x=\"184\" y=\"44\" width=\"72\" height=\"72\" rx=\"8\"/>"), "{}", svg);
+    assert!(svg.contains("<title>0--  a'</title>"));
+}
@@ -14,35 +14,171 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-extern crate subint;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(any(feature = "std", test))]
+extern crate core;
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "serde")]
+extern crate serde;
 mod bits;
+mod block;
+#[cfg(feature = "std")]
+mod checkpoint;
+mod cube;
+mod error;
 mod masked_count;
-
-#[cfg(feature = "c-abi")]
-pub mod c;
-
+#[cfg(test)]
+mod test_random;
+mod implicant;
+mod prelude;
+mod variables;
+
+#[cfg(feature = "std")]
+pub mod aiger;
+#[cfg(feature = "std")]
+pub mod blif;
+pub mod emit;
+pub mod esop;
+pub mod expr;
+pub mod hazard;
+pub mod kmap;
+pub mod minimize;
+pub mod mv;
+#[cfg(feature = "std")]
+pub mod pla;
+pub mod properties;
+pub mod reed_muller;
+pub mod unate;
+pub mod verify;
+
+#[cfg(feature = "serde")]
+pub mod serde_cubes;
+
+use core::cell::Cell;
+use core::convert::Infallible;
+use core::mem;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
 use bits::Bitset;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
+pub use block::{BlockSampler, PerPoint};
+pub use error::Error;
+pub use implicant::{Cover, Implicant};
+pub use variables::{Variable, Variables, VariablesError};
+
+#[cfg(feature = "std")]
 type ChunkMap = HashMap<u32, Bitset>;
+#[cfg(not(feature = "std"))]
+type ChunkMap = BTreeMap<u32, Bitset>;
+type CheckpointFn<'a, E> = dyn FnMut(&Checkpoint) -> Result<(), E> + 'a;
+
+/// What `try_generate` encountered along the way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Statistics {
+    /// Number of reported implicants, including the prime ones.
+    pub implicants: u64,
+    /// Number of reported prime implicants.
+    pub primes: u64,
+    /// Most bytes that were occupied by chunks at the same time.
+    pub peak_bytes: usize,
+}
 
-struct Context<'a, 'b> {
-    sampling_fn: &'a Fn(u32) -> bool,
-    report_fn: &'b mut FnMut(u32, u32, bool),
+/// State of a run after all implicants up to some rank have been reported.
+/// Enough to continue with `resume`, even in another process.
+pub struct Checkpoint {
     arity: u32,
+    rank: u32,
+    stats: Statistics,
+    chunks: ChunkMap,
 }
 
-impl<'x, 'y> Context<'x, 'y> {
-    fn new_chunk(&self) -> Bitset {
-        Bitset::of(self.arity)
+impl Checkpoint {
+    /// Number of input variables of the function.
+    pub fn arity(&self) -> u32 {
+        self.arity
+    }
+
+    /// All implicants up to this many `M`s have been reported.
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    /// What has been reported up to this point.
+    pub fn statistics(&self) -> Statistics {
+        self.stats
     }
+}
+
+enum Sampler<'a, E: 'a> {
+    Point(&'a dyn Fn(u32) -> Result<bool, E>),
+    Block(&'a dyn BlockSampler),
+}
 
-    fn insert_chunk<'a>(&self, into: &'a mut ChunkMap, at: u32) -> &'a mut Bitset {
-        into.entry(at).or_insert_with(|| self.new_chunk())
+struct Context<'a, 'b, E: 'a> {
+    sampler: Sampler<'a, E>,
+    report_fn: &'b mut dyn FnMut(u32, u32, bool),
+    checkpoint_fn: Option<&'b mut CheckpointFn<'b, E>>,
+    arity: u32,
+    memory_limit: usize,
+    peak_bytes: Cell<usize>,
+    stats: Statistics,
+}
+
+#[cfg(test)]
+type TestContext<'a, 'b> = Context<'a, 'b, ()>;
+
+impl<'x, 'y, E> Context<'x, 'y, E> {
+    fn new(sampling_fn: &'x dyn Fn(u32) -> Result<bool, E>,
+           report_fn: &'y mut dyn FnMut(u32, u32, bool),
+           arity: u32)
+           -> Self {
+        Self::with_sampler(Sampler::Point(sampling_fn), report_fn, arity)
+    }
+
+    fn with_sampler(sampler: Sampler<'x, E>,
+                    report_fn: &'y mut dyn FnMut(u32, u32, bool),
+                    arity: u32)
+                    -> Self {
+        Context {
+            sampler,
+            report_fn,
+            checkpoint_fn: None,
+            arity,
+            memory_limit: usize::MAX,
+            peak_bytes: Cell::new(0),
+            stats: Statistics::default(),
+        }
+    }
+
+    /// Makes sure the chunk exists.  `elsewhere` is the number of chunks
+    /// that are alive outside of `into`, for the sake of the memory limit.
+    fn insert_chunk<'a>(&self, into: &'a mut ChunkMap, at: u32, elsewhere: usize)
+                        -> Result<&'a mut Bitset, Error<E>> {
+        if !into.contains_key(&at) {
+            let bytes = Bitset::bytes_for(self.arity);
+            let required = bytes.saturating_mul(elsewhere + into.len() + 1);
+            if required > self.memory_limit {
+                return Err(Error::MemoryLimitExceeded {
+                    limit: self.memory_limit,
+                    required,
+                });
+            }
+            let chunk = Bitset::try_of(self.arity).ok_or(Error::AllocationFailed { bytes })?;
+            into.insert(at, chunk);
+            self.peak_bytes.set(self.peak_bytes.get().max(required));
+        }
+        Ok(into.get_mut(&at).unwrap())
     }
 }
 
-fn build_rank_0(ctx: &Context, into: &mut ChunkMap) {
+fn build_rank_0<E>(ctx: &Context<E>, into: &mut ChunkMap) -> Result<(), Error<E>> {
     assert!(ctx.arity < 32,
             "Can only handle at most 31 bits, but tried {} bits",
             ctx.arity);
@@ -53,11 +189,22 @@ fn build_rank_0(ctx: &Context, into: &mut ChunkMap) {
     // Need to end lifetime of 'chunk' before we remove it from the container,
     // so wrap it into a separate scope.
     {
-        let chunk: &mut Bitset = ctx.insert_chunk(into, 0);
-        // I could probably extend that to include 32, but then this would overflow on x86:
-        for i in 0..(1 << ctx.arity) {
-            if (ctx.sampling_fn)(i) {
-                chunk.set(i);
+        let chunk: &mut Bitset = ctx.insert_chunk(into, 0, 0)?;
+        match ctx.sampler {
+            Sampler::Point(sampling_fn) => {
+                // I could probably extend that to include 32, but then this would overflow on x86:
+                for i in 0..(1 << ctx.arity) {
+                    if sampling_fn(i).map_err(Error::User)? {
+                        chunk.set(i);
+                    }
+                }
+            }
+            Sampler::Block(sampler) => {
+                chunk.fill_words(|words| {
+                    for (i, block) in words.chunks_mut(block::BLOCK_WORDS).enumerate() {
+                        sampler.sample_block((i * block::BLOCK_WORDS * 64) as u32, block);
+                    }
+                });
             }
         }
         is_any = chunk.is_any();
@@ -66,18 +213,19 @@ fn build_rank_0(ctx: &Context, into: &mut ChunkMap) {
     if !is_any {
         into.remove(&0);
     }
+    Ok(())
 }
 
 #[cfg(test)]
-fn test_sample_mod3(v: u32) -> bool {
-    (v % 3) == 0
+fn test_sample_mod3(v: u32) -> Result<bool, ()> {
+    Ok((v % 3) == 0)
 }
 #[cfg(test)]
-fn test_sample_mux(v: u32) -> bool {
-    1 == 1 & (v >> (1 + (v & 1)))
+fn test_sample_mux(v: u32) -> Result<bool, ()> {
+    Ok(1 == 1 & (v >> (1 + (v & 1))))
 }
 #[cfg(test)]
-fn test_sample_fail(_: u32) -> bool {
+fn test_sample_fail(_: u32) -> Result<bool, ()> {
     panic!("But there is nothing to sample?!");
 }
 
@@ -89,15 +237,12 @@ fn test_report_fail(_: u32, _: u32, _: bool) {
 #[test]
 fn test_build_0() {
     // Prepare
-    let ctx = Context {
-        sampling_fn: &test_sample_mod3,
-        report_fn: &mut test_report_fail,
-        arity: 3,
-    };
+    let mut report = test_report_fail;
+    let ctx = TestContext::new(&test_sample_mod3, &mut report, 3);
     let mut chunks = ChunkMap::new();
 
     // Call under test
-    build_rank_0(&ctx, &mut chunks);
+    build_rank_0(&ctx, &mut chunks).unwrap();
 
     // Check
     assert_eq!(1, chunks.len());
@@ -115,15 +260,12 @@ fn test_build_0() {
 #[test]
 fn test_build_0_full() {
     // Prepare
-    let ctx = Context {
-        sampling_fn: &|_| true,
-        report_fn: &mut test_report_fail,
-        arity: 3,
-    };
+    let mut report = test_report_fail;
+    let ctx = TestContext::new(&|_| Ok(true), &mut report, 3);
     let mut chunks = ChunkMap::new();
 
     // Call under test
-    build_rank_0(&ctx, &mut chunks);
+    build_rank_0(&ctx, &mut chunks).unwrap();
 
     // Check
     assert_eq!(1, chunks.len());
@@ -141,31 +283,29 @@ fn test_build_0_full() {
 #[test]
 fn test_build_0_empty() {
     // Prepare
-    let ctx = Context {
-        sampling_fn: &|_| false,
-        report_fn: &mut test_report_fail,
-        arity: 3,
-    };
+    let mut report = test_report_fail;
+    let ctx = TestContext::new(&|_| Ok(false), &mut report, 3);
     let mut chunks = ChunkMap::new();
 
     // Call under test
-    build_rank_0(&ctx, &mut chunks);
+    build_rank_0(&ctx, &mut chunks).unwrap();
 
     // Check
     assert_eq!(0, chunks.len());
 }
 
-fn build_rank_n(ctx: &Context, rank: u32, into: &mut ChunkMap, from: &ChunkMap) {
+fn build_rank_n<E>(ctx: &Context<E>, rank: u32, into: &mut ChunkMap, from: &ChunkMap)
+                   -> Result<(), Error<E>> {
     assert!(into.is_empty());
 
     /* Quick path in case there's nothing to do *at all*. */
     if from.is_empty() {
-        return;
+        return Ok(());
     }
 
-    let arity_mask = subint::of(ctx.arity);
+    let arity_mask = masked_count::low(ctx.arity);
     // For each destination chunk:
-    for mask_m in arity_mask.permute(rank) {
+    for mask_m in masked_count::of_weight(ctx.arity, rank) {
         // Pick a subchunk from which we're going to read
         let overmask_m = mask_m & (mask_m - 1);
         let subchunk: Option<&Bitset> = from.get(&overmask_m);
@@ -179,11 +319,11 @@ fn build_rank_n(ctx: &Context, rank: u32, into: &mut ChunkMap, from: &ChunkMap)
         // Need to end lifetime of 'chunk' before we remove it from the container,
         // so wrap it into a separate scope.
         {
-            let chunk: &mut Bitset = ctx.insert_chunk(into, mask_m);
+            let chunk: &mut Bitset = ctx.insert_chunk(into, mask_m, from.len())?;
             let collapsed_dim = mask_m & !overmask_m;
             assert_eq!(1, collapsed_dim.count_ones(), "{}", collapsed_dim);
             // For each face:
-            for i in masked_count::up(arity_mask.invert(mask_m)) {
+            for i in masked_count::up(arity_mask & !mask_m) {
                 // If both "sides" of the current "face" are implicants,
                 // then the current "face" is an implicant, too.
                 if subchunk.is(i) && subchunk.is(i | collapsed_dim) {
@@ -198,24 +338,22 @@ fn build_rank_n(ctx: &Context, rank: u32, into: &mut ChunkMap, from: &ChunkMap)
             into.remove(&mask_m);
         }
     }
+    Ok(())
 }
 
 #[test]
 fn test_build_n() {
     // Prepare
-    let ctx = Context {
-        sampling_fn: &test_sample_mux,
-        report_fn: &mut test_report_fail,
-        arity: 3,
-    };
+    let mut report = test_report_fail;
+    let ctx = TestContext::new(&test_sample_mux, &mut report, 3);
     let mut chunks_from = ChunkMap::new();
-    build_rank_0(&ctx, &mut chunks_from);
+    build_rank_0(&ctx, &mut chunks_from).unwrap();
     assert_eq!(1, chunks_from.len());
     let chunks_from = chunks_from;
     let mut chunks_into = ChunkMap::new();
 
     // Call under test
-    build_rank_n(&ctx, 1, &mut chunks_into, &chunks_from);
+    build_rank_n(&ctx, 1, &mut chunks_into, &chunks_from).unwrap();
 
     // Check
     assert_eq!(3, chunks_into.len());
@@ -239,19 +377,16 @@ fn test_build_n() {
 #[test]
 fn test_build_n_empty() {
     // Prepare
-    let ctx = Context {
-        sampling_fn: &test_sample_fail,
-        report_fn: &mut test_report_fail,
-        arity: 3,
-    };
+    let mut report = test_report_fail;
+    let ctx = TestContext::new(&test_sample_fail, &mut report, 3);
     let mut chunks_from = ChunkMap::new();
-    ctx.insert_chunk(&mut chunks_from, 0).set(0);
+    ctx.insert_chunk(&mut chunks_from, 0, 0).unwrap().set(0);
     assert_eq!(1, chunks_from.len());
     let chunks_from = chunks_from;
     let mut chunks_into = ChunkMap::new();
 
     // Call under test
-    build_rank_n(&ctx, 1, &mut chunks_into, &chunks_from);
+    build_rank_n(&ctx, 1, &mut chunks_into, &chunks_from).unwrap();
 
     // Check
     assert_eq!(0, chunks_into.len());
@@ -260,27 +395,24 @@ fn test_build_n_empty() {
 #[test]
 fn test_build_n_empty_imm() {
     // Prepare
-    let ctx = Context {
-        sampling_fn: &test_sample_fail,
-        report_fn: &mut test_report_fail,
-        arity: 3,
-    };
+    let mut report = test_report_fail;
+    let ctx = TestContext::new(&test_sample_fail, &mut report, 3);
     let chunks_from = ChunkMap::new();
     let mut chunks_into = ChunkMap::new();
 
     // Call under test
-    build_rank_n(&ctx, 1, &mut chunks_into, &chunks_from);
+    build_rank_n(&ctx, 1, &mut chunks_into, &chunks_from).unwrap();
 
     // Check
     assert_eq!(0, chunks_into.len());
 }
 
-fn report_0n(ctx: &mut Context, chunks: &ChunkMap) {
-    let arity_mask = subint::of(ctx.arity);
+fn report_0n<E>(ctx: &mut Context<E>, chunks: &ChunkMap) {
+    let arity_mask = masked_count::low(ctx.arity);
     // For each chunk:
     for (&mask_m, chunk) in chunks {
         // For each face:
-        for face in masked_count::up(arity_mask.invert(mask_m)) {
+        for face in masked_count::up(arity_mask & !mask_m) {
             if !chunk.is(face) {
                 /* If it's not an implicant, then it's not an implicant.
                  * Furthermore, it's definitely not a prime implicant. */
@@ -289,7 +421,7 @@ fn report_0n(ctx: &mut Context, chunks: &ChunkMap) {
             let mut has_peer = false;
             // For each potential peer:
             // TODO: Compute the relevant 'peer_dir's more cleverly.
-            for peer_dir in arity_mask.permute(1) {
+            for peer_dir in masked_count::of_weight(ctx.arity, 1) {
                 // If that peer exists and is on:
                 if (mask_m & peer_dir) == 0 && chunk.is(face ^ peer_dir) {
                     // … then we found a more general implicant.
@@ -301,6 +433,10 @@ fn report_0n(ctx: &mut Context, chunks: &ChunkMap) {
             // general implicants.  So if we still haven't found a peer,
             // then this is actually a prime implicant!
             let is_prime = !has_peer;
+            ctx.stats.implicants += 1;
+            if is_prime {
+                ctx.stats.primes += 1;
+            }
             (ctx.report_fn)(mask_m, face, is_prime);
         }
     }
@@ -314,14 +450,10 @@ fn test_report() {
         let mut report = |mask_m: u32, mask_nonm: u32, prime: bool| {
             report_target.push((mask_m, mask_nonm, prime));
         };
-        let mut ctx = Context {
-            sampling_fn: &test_sample_fail,
-            report_fn: &mut report,
-            arity: 3,
-        };
+        let mut ctx = TestContext::new(&test_sample_fail, &mut report, 3);
         let mut chunks_from = ChunkMap::new();
         {
-            let chunk = ctx.insert_chunk(&mut chunks_from, 0);
+            let chunk = ctx.insert_chunk(&mut chunks_from, 0, 0).unwrap();
             chunk.set(0b000);
             chunk.set(0b110);
             chunk.set(0b111);
@@ -355,27 +487,246 @@ fn test_report() {
     assert_eq!(vec![true, true, true], seen);
 }
 
-pub fn generate(sampling_fn: &Fn(u32) -> bool,
-                report_fn: &mut FnMut(u32, u32, bool),
+pub fn generate(sampling_fn: &dyn Fn(u32) -> bool,
+                report_fn: &mut dyn FnMut(u32, u32, bool),
                 arity: u32) {
-    let mut ctx = Context {
-        sampling_fn,
-        report_fn,
-        arity,
-    };
+    let infallible = |v| -> Result<bool, Infallible> { Ok(sampling_fn(v)) };
+    if let Err(e) = try_generate(&infallible, report_fn, arity) {
+        panic!("{}", e);
+    }
+}
+
+/// Like `generate`, but reports bad input and sampling failures as `Err`
+/// instead of panicking.  The first error returned by `sampling_fn` stops
+/// sampling immediately; in that case, nothing has been reported yet.
+pub fn try_generate<E>(sampling_fn: &dyn Fn(u32) -> Result<bool, E>,
+                       report_fn: &mut dyn FnMut(u32, u32, bool),
+                       arity: u32)
+                       -> Result<Statistics, Error<E>> {
+    try_generate_limited(sampling_fn, report_fn, arity, usize::MAX)
+}
+
+/// Like `try_generate`, but first checks that `variables` has exactly one
+/// variable per bit of `arity`.  Only the check uses `variables`: the
+/// implicants are reported in bits, as always, so map them yourself with
+/// `Variables::to_signals`, or hand them to the `_named` exporters.
+pub fn try_generate_checked<E>(sampling_fn: &dyn Fn(u32) -> Result<bool, E>,
+                               report_fn: &mut dyn FnMut(u32, u32, bool),
+                               arity: u32,
+                               variables: &Variables)
+                               -> Result<Statistics, Error<E>> {
+    variables.check_arity(arity).map_err(Error::Variables)?;
+    try_generate(sampling_fn, report_fn, arity)
+}
+
+/// Like `try_generate`, but fails with `Error::MemoryLimitExceeded` before
+/// the chunks would occupy more than `memory_limit` bytes at once.
+/// Note that implicants may already have been reported by then.
+pub fn try_generate_limited<E>(sampling_fn: &dyn Fn(u32) -> Result<bool, E>,
+                               report_fn: &mut dyn FnMut(u32, u32, bool),
+                               arity: u32,
+                               memory_limit: usize)
+                               -> Result<Statistics, Error<E>> {
+    let mut ctx = Context::new(sampling_fn, report_fn, arity);
+    ctx.memory_limit = memory_limit;
+    run(ctx)
+}
+
+/// Like `generate`, but samples many inputs per call, which saves the
+/// overhead of calling through a trait object for each single input.
+pub fn generate_blocks(sampler: &dyn BlockSampler,
+                       report_fn: &mut dyn FnMut(u32, u32, bool),
+                       arity: u32) {
+    let ctx: Context<Infallible> = Context::with_sampler(Sampler::Block(sampler), report_fn, arity);
+    if let Err(e) = run(ctx) {
+        panic!("{}", e);
+    }
+}
+
+/// Like `try_generate`, but hands a `Checkpoint` to `checkpoint_fn` after
+/// each rank, except the last.  An error of `checkpoint_fn` stops the run
+/// and is returned as `Error::Checkpoint`.
+pub fn try_generate_checkpointed<E>(sampling_fn: &dyn Fn(u32) -> Result<bool, E>,
+                                    report_fn: &mut dyn FnMut(u32, u32, bool),
+                                    arity: u32,
+                                    checkpoint_fn: &mut dyn FnMut(&Checkpoint) -> Result<(), E>)
+                                    -> Result<Statistics, Error<E>> {
+    let mut ctx = Context::new(sampling_fn, report_fn, arity);
+    ctx.checkpoint_fn = Some(checkpoint_fn);
+    run(ctx)
+}
+
+fn unreachable_sampler<E>(_: u32) -> Result<bool, E> {
+    unreachable!("Resumed runs never sample");
+}
+
+/// Continues the run that produced `checkpoint`, reporting only the
+/// implicants with more than `checkpoint.rank()` `M`s.  The returned
+/// statistics include everything reported before the checkpoint.
+pub fn resume<E>(checkpoint: Checkpoint,
+                 report_fn: &mut dyn FnMut(u32, u32, bool),
+                 checkpoint_fn: &mut dyn FnMut(&Checkpoint) -> Result<(), E>)
+                 -> Result<Statistics, Error<E>> {
+    let mut ctx = Context::new(&unreachable_sampler, report_fn, checkpoint.arity);
+    ctx.checkpoint_fn = Some(checkpoint_fn);
+    ctx.stats = checkpoint.stats;
+    ctx.peak_bytes.set(checkpoint.stats.peak_bytes);
+    run_from(ctx, checkpoint.rank, checkpoint.chunks)
+}
+
+fn run<E>(mut ctx: Context<E>) -> Result<Statistics, Error<E>> {
+    if ctx.arity >= 32 {
+        return Err(Error::UnsupportedArity(ctx.arity));
+    }
     let mut map0 = ChunkMap::new();
-    let mut map1 = ChunkMap::new();
-    build_rank_0(&ctx, &mut map0);
+    build_rank_0(&ctx, &mut map0)?;
     report_0n(&mut ctx, &map0);
+    save_checkpoint(&mut ctx, 0, &mut map0)?;
+    run_from(ctx, 0, map0)
+}
 
-    for rank in 1..ctx.arity {
-        let (from, into) = if rank % 2 == 0 {
-            (&mut map1, &mut map0)
-        } else {
+/// Continues after all implicants up to rank `done`, whose chunks are `map0`.
+fn run_from<E>(mut ctx: Context<E>, done: u32, mut map0: ChunkMap)
+               -> Result<Statistics, Error<E>> {
+    let mut map1 = ChunkMap::new();
+    // Up to and including `arity`, which only the all-`M` cube has.
+    for rank in (done + 1)..=ctx.arity {
+        let (from, into) = if (rank - done) % 2 == 1 {
             (&mut map0, &mut map1)
+        } else {
+            (&mut map1, &mut map0)
         };
-        build_rank_n(&ctx, rank, into, from);
+        build_rank_n(&ctx, rank, into, from)?;
         from.clear();
         report_0n(&mut ctx, into);
+        save_checkpoint(&mut ctx, rank, into)?;
     }
+
+    ctx.stats.peak_bytes = ctx.peak_bytes.get();
+    Ok(ctx.stats)
+}
+
+fn save_checkpoint<E>(ctx: &mut Context<E>, rank: u32, chunks: &mut ChunkMap)
+                      -> Result<(), Error<E>> {
+    if rank == ctx.arity {
+        return Ok(());
+    }
+    if let Some(ref mut checkpoint_fn) = ctx.checkpoint_fn {
+        let mut stats = ctx.stats;
+        stats.peak_bytes = ctx.peak_bytes.get();
+        // Lend the chunks instead of copying them.
+        let checkpoint = Checkpoint {
+            arity: ctx.arity,
+            rank,
+            stats,
+            chunks: mem::take(chunks),
+        };
+        let result = checkpoint_fn(&checkpoint);
+        *chunks = checkpoint.chunks;
+        result.map_err(Error::Checkpoint)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_try_generate() {
+    let mut reported = 0;
+    let stats = try_generate(&test_sample_mux, &mut |_, _, _| reported += 1, 3).unwrap();
+    assert_eq!(7, reported);
+    assert_eq!(Statistics {
+                   implicants: 7,
+                   primes: 3,
+                   // Rank 0 and rank 1 chunks, each with 8 bits:
+                   peak_bytes: 4 * 8,
+               },
+               stats);
+}
+
+#[test]
+fn test_generate_constant_true() {
+    // The only prime is the all-`M` cube, at rank `arity`.
+    for arity in 0..5 {
+        let mut primes = Vec::new();
+        generate(&|_| true, &mut |m, v, p| if p { primes.push((m, v)) }, arity);
+        assert_eq!(vec![((1 << arity) - 1, 0)], primes, "arity {}", arity);
+    }
+}
+
+#[test]
+fn test_generate_blocks() {
+    for arity in 0..9 {
+        let sample = |v: u32| v % 3 == 1 || v % 7 == 2;
+        let mut expected = Vec::new();
+        generate(&sample, &mut |m, v, p| expected.push((m, v, p)), arity);
+        let mut actual = Vec::new();
+        generate_blocks(&PerPoint::new(sample, arity), &mut |m, v, p| actual.push((m, v, p)), arity);
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual, "arity {}", arity);
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_resume() {
+    let sample = |v: u32| -> Result<bool, ()> { Ok(v % 7 != 3 && v % 11 != 4) };
+    let mut expected = Vec::new();
+    let stats = try_generate(&sample, &mut |m, v, p| expected.push((m, v, p)), 8).unwrap();
+    expected.sort();
+
+    for stop in 0..8 {
+        // Crash right after the checkpoint at rank `stop`.
+        let mut saved = None;
+        let mut actual = Vec::new();
+        let err = try_generate_checkpointed(&sample, &mut |m, v, p| actual.push((m, v, p)), 8,
+                                            &mut |checkpoint| {
+            if checkpoint.rank() < stop {
+                return Ok(());
+            }
+            let mut bytes = Vec::new();
+            checkpoint.write_to(&mut bytes).unwrap();
+            saved = Some(bytes);
+            Err(())
+        });
+        assert_eq!(Err(Error::Checkpoint(())), err);
+
+        let checkpoint = Checkpoint::read_from(&mut &saved.unwrap()[..]).unwrap();
+        assert_eq!(stop, checkpoint.rank());
+        assert_eq!(actual.len() as u64, checkpoint.statistics().implicants);
+        let mut checkpoints = 0;
+        let resumed = resume(checkpoint, &mut |m, v, p| actual.push((m, v, p)),
+                             &mut |_| -> Result<(), ()> { checkpoints += 1; Ok(()) }).unwrap();
+        assert_eq!(7 - stop, checkpoints);
+        actual.sort();
+        assert_eq!(expected, actual);
+        assert_eq!(stats.implicants, resumed.implicants);
+        assert_eq!(stats.primes, resumed.primes);
+    }
+}
+
+#[test]
+fn test_try_generate_checked() {
+    let vars = Variables::from_names(&["a", "b", "c"]).unwrap();
+    let mut reported = 0;
+    assert!(try_generate_checked(&test_sample_mux, &mut |_, _, _| reported += 1, 3, &vars).is_ok());
+    assert_eq!(7, reported);
+    assert_eq!(Err(Error::Variables(VariablesError::ArityMismatch { variables: 3, arity: 4 })),
+               try_generate_checked(&test_sample_mux, &mut test_report_fail, 4, &vars));
+}
+
+#[test]
+fn test_try_generate_errors() {
+    assert_eq!(Err(Error::UnsupportedArity(32)),
+               try_generate(&test_sample_fail, &mut test_report_fail, 32));
+
+    let calls = Cell::new(0);
+    let sample = |v: u32| {
+        calls.set(calls.get() + 1);
+        if v == 5 { Err("boom") } else { Ok(true) }
+    };
+    assert_eq!(Err(Error::User("boom")), try_generate(&sample, &mut test_report_fail, 4));
+    assert_eq!(6, calls.get());
+
+    let result = try_generate_limited(&test_sample_mux, &mut |_, _, _| {}, 3, 3 * 8);
+    assert_eq!(Err(Error::MemoryLimitExceeded { limit: 24, required: 32 }), result);
 }
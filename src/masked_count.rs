@@ -51,6 +51,66 @@ impl Iterator for UpIter {
     }
 }
 
+/// The lowest `nbits` bits set.
+pub fn low(nbits: u32) -> u32 {
+    assert!(nbits < 32);
+    (1 << nbits) - 1
+}
+
+/// Iterates over all masks of the lowest `nbits` bits with exactly
+/// `weight` bits set, in ascending order.
+pub struct WeightIter {
+    next: u64,
+    end: u64,
+}
+
+pub fn of_weight(nbits: u32, weight: u32) -> WeightIter {
+    assert!(nbits < 32);
+    WeightIter {
+        next: if weight <= nbits { (1 << weight) - 1 } else { 1 << nbits },
+        end: 1 << nbits,
+    }
+}
+
+impl Iterator for WeightIter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let ret = self.next;
+        if ret >= self.end {
+            return None;
+        }
+        if ret == 0 {
+            // The only mask of weight 0.
+            self.next = self.end;
+        } else {
+            // Gosper's hack: the next larger number with the same weight.
+            let lowest = ret & ret.wrapping_neg();
+            let ripple = ret + lowest;
+            self.next = (((ripple ^ ret) >> 2) / lowest) | ripple;
+        }
+        Some(ret as u32)
+    }
+}
+
+#[test]
+fn test_of_weight() {
+    assert_eq!(vec![0], of_weight(0, 0).collect::<Vec<_>>());
+    assert_eq!(vec![0], of_weight(3, 0).collect::<Vec<_>>());
+    assert_eq!(vec![1, 2, 4], of_weight(3, 1).collect::<Vec<_>>());
+    assert_eq!(vec![3, 5, 6], of_weight(3, 2).collect::<Vec<_>>());
+    assert_eq!(vec![7], of_weight(3, 3).collect::<Vec<_>>());
+    assert_eq!(Vec::<u32>::new(), of_weight(3, 4).collect::<Vec<_>>());
+    assert_eq!(vec![0x7fff_ffff], of_weight(31, 31).collect::<Vec<_>>());
+    for nbits in 0..11 {
+        for weight in 0..(nbits + 1) {
+            let expected: Vec<u32> =
+                (0..(1 << nbits)).filter(|m: &u32| m.count_ones() == weight).collect();
+            assert_eq!(expected, of_weight(nbits, weight).collect::<Vec<_>>());
+        }
+    }
+}
+
 #[test]
 fn test_count_simple() {
     let mut i = up(0xCAFEBABE);
@@ -0,0 +1,389 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Selection of a cheapest cover among the prime implicants.
+//! This is the classic (weighted) unate covering problem, solved exactly
+//! by branch and bound.  Expect exponential running time on nasty inputs.
+
+use implicant::{Cover, Implicant};
+use prelude::*;
+
+/// What a sum-of-products realization costs on the target.
+/// Both literal vectors are indexed by bit position, and must have
+/// exactly one entry per variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostModel {
+    /// Cost of each uncomplemented literal (`1` in the implicant).
+    pub positive: Vec<u64>,
+    /// Cost of each complemented literal (`0` in the implicant).
+    pub negative: Vec<u64>,
+    /// Cost of each product term, independent of its literals.
+    pub term: u64,
+}
+
+impl CostModel {
+    pub fn uniform(arity: u32, literal: u64, term: u64) -> Self {
+        CostModel {
+            positive: vec![literal; arity as usize],
+            negative: vec![literal; arity as usize],
+            term,
+        }
+    }
+
+    /// Only counts product terms, i.e., yields a minimum-cardinality cover.
+    pub fn terms(arity: u32) -> Self {
+        Self::uniform(arity, 0, 1)
+    }
+
+    pub fn arity(&self) -> u32 {
+        assert_eq!(self.positive.len(), self.negative.len(),
+                   "Cost model has inconsistent lengths");
+        self.positive.len() as u32
+    }
+
+    pub fn cost_of(&self, imp: &Implicant) -> u64 {
+        let mut cost = self.term;
+        for i in 0..self.arity() {
+            let bit = 1 << i;
+            if imp.mask_gap & bit != 0 {
+                continue;
+            }
+            cost += if imp.value & bit != 0 {
+                self.positive[i as usize]
+            } else {
+                self.negative[i as usize]
+            };
+        }
+        cost
+    }
+
+    pub fn cost_of_cover(&self, cover: &[Implicant]) -> u64 {
+        cover.iter().map(|imp| self.cost_of(imp)).sum()
+    }
+}
+
+/// Computes a minimum-cost cover of `sampling_fn`.
+/// Note that `sampling_fn` is called twice for each input.
+pub fn minimize(sampling_fn: &dyn Fn(u32) -> bool, arity: u32, costs: &CostModel) -> Cover {
+    minimize_with_dc(sampling_fn, &|_| false, arity, costs)
+}
+
+/// Computes a minimum-cost cover of `on_fn`, which may or may not cover
+/// the inputs for which `dc_fn` holds.  Inputs where both hold are
+/// treated as don't-cares.
+/// Note that both functions are called twice for each input.
+pub fn minimize_with_dc(on_fn: &dyn Fn(u32) -> bool,
+                        dc_fn: &dyn Fn(u32) -> bool,
+                        arity: u32,
+                        costs: &CostModel)
+                        -> Cover {
+    assert_eq!(arity, costs.arity(),
+               "Cost model is for {} variables, but function has {}",
+               costs.arity(), arity);
+    let mut primes = Vec::new();
+    {
+        let mut collect = |mask_gap: u32, value: u32, is_prime: bool| {
+            if is_prime {
+                primes.push(Implicant::new(mask_gap, value));
+            }
+        };
+        ::generate(&|v| on_fn(v) || dc_fn(v), &mut collect, arity);
+    }
+    let on_set: Vec<u32> = (0..(1u32 << arity)).filter(|&v| on_fn(v) && !dc_fn(v)).collect();
+
+    let chosen = select_cover(&primes, &on_set, costs)
+        .expect("Primes must cover the function");
+    Cover::new(arity, chosen)
+}
+
+/// Picks a minimum-cost subset of `candidates` that contains every point
+/// of `on_set`.  Points not in `on_set` are implicitly don't-cares.
+/// Returns `None` if even all candidates together don't suffice.
+/// The result is sorted.
+pub fn select_cover(candidates: &[Implicant],
+                    on_set: &[u32],
+                    costs: &CostModel)
+                    -> Option<Vec<Implicant>> {
+    let required: Vec<Implicant> = on_set.iter().map(|&p| Implicant::new(0, p)).collect();
+    select_cover_of_cubes(candidates, &required, costs)
+}
+
+/// Like `select_cover`, but every cube of `required` must lie entirely
+/// inside a single chosen candidate.
+pub fn select_cover_of_cubes(candidates: &[Implicant],
+                             required: &[Implicant],
+                             costs: &CostModel)
+                             -> Option<Vec<Implicant>> {
+    let costs: Vec<u64> = candidates.iter().map(|c| costs.cost_of(c)).collect();
+    let rows: Vec<Vec<usize>> = required.iter()
+        .map(|r| (0..candidates.len()).filter(|&c| candidates[c].covers(r)).collect())
+        .collect();
+    solve_covering(&costs, &rows).map(|chosen| {
+        let mut cover: Vec<Implicant> = chosen.into_iter().map(|c| candidates[c]).collect();
+        cover.sort();
+        cover
+    })
+}
+
+/// The covering problem itself, for other kinds of candidates: picks a
+/// minimum-cost set of columns such that every row contains one of them.
+/// Each row lists its columns in ascending order.  Returns `None` if that
+/// is impossible, and otherwise the chosen columns in the order chosen.
+pub fn solve_covering(costs: &[u64], rows: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let problem = Problem {
+        costs: costs.to_vec(),
+        rows: rows.to_vec(),
+    };
+    let state = State {
+        chosen: Vec::new(),
+        cost: 0,
+        points: (0..rows.len()).collect(),
+        alive: vec![true; costs.len()],
+    };
+
+    let mut best = None;
+    problem.solve(state, &mut best);
+    best.map(|(_, chosen)| chosen)
+}
+
+struct Problem {
+    /// Cost of each candidate.
+    costs: Vec<u64>,
+    /// For each point, the (ascending) candidates which contain it.
+    rows: Vec<Vec<usize>>,
+}
+
+#[derive(Clone)]
+struct State {
+    chosen: Vec<usize>,
+    cost: u64,
+    /// Points that still need to be covered.
+    points: Vec<usize>,
+    /// Candidates that have neither been chosen nor excluded.
+    alive: Vec<bool>,
+}
+
+/// Both slices must be ascending.
+fn is_subset(sub: &[usize], sup: &[usize]) -> bool {
+    let mut sup = sup.iter();
+    sub.iter().all(|x| sup.any(|y| y == x))
+}
+
+impl Problem {
+    fn live(&self, state: &State, point: usize) -> Vec<usize> {
+        self.rows[point].iter().cloned().filter(|&c| state.alive[c]).collect()
+    }
+
+    fn choose(&self, state: &mut State, cand: usize) {
+        state.cost += self.costs[cand];
+        state.chosen.push(cand);
+        state.alive[cand] = false;
+        let rows = &self.rows;
+        state.points.retain(|&p| rows[p].binary_search(&cand).is_err());
+    }
+
+    /// Applies essential candidates, row dominance and column dominance
+    /// until nothing changes.  Returns whether the state is still feasible.
+    fn reduce(&self, state: &mut State) -> bool {
+        loop {
+            let mut changed = false;
+
+            // Essential candidates:
+            let mut i = 0;
+            while i < state.points.len() {
+                let live = self.live(state, state.points[i]);
+                match live.len() {
+                    0 => return false,
+                    1 => {
+                        self.choose(state, live[0]);
+                        changed = true;
+                        i = 0;
+                    }
+                    _ => i += 1,
+                }
+            }
+
+            // Row dominance: Covering a point whose options are a subset
+            // of another point's options also covers the other one.
+            let lives: Vec<Vec<usize>> =
+                state.points.iter().map(|&p| self.live(state, p)).collect();
+            let mut keep = vec![true; lives.len()];
+            for a in 0..lives.len() {
+                if !keep[a] {
+                    continue;
+                }
+                for b in 0..lives.len() {
+                    if a != b && keep[b] && is_subset(&lives[a], &lives[b]) {
+                        keep[b] = false;
+                        changed = true;
+                    }
+                }
+            }
+            let mut keep = keep.into_iter();
+            state.points.retain(|_| keep.next().unwrap());
+
+            // Column dominance: A candidate is useless if another one
+            // covers at least the same points, for at most the same cost.
+            let mut cols = vec![Vec::new(); self.costs.len()];
+            for (j, &p) in state.points.iter().enumerate() {
+                for c in self.live(state, p) {
+                    cols[c].push(j);
+                }
+            }
+            for p in 0..cols.len() {
+                if !state.alive[p] {
+                    continue;
+                }
+                let dominated = cols[p].is_empty() ||
+                    (0..cols.len()).any(|q| {
+                        q != p && state.alive[q] && self.costs[q] <= self.costs[p] &&
+                        is_subset(&cols[p], &cols[q]) &&
+                        (q < p || self.costs[q] < self.costs[p] ||
+                         cols[p].len() < cols[q].len())
+                    });
+                if dominated {
+                    state.alive[p] = false;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    /// Sum of the cheapest options of pairwise independent points.
+    /// Each of them needs a distinct candidate, so this can't overestimate.
+    fn lower_bound(&self, state: &State) -> u64 {
+        let mut used = vec![false; self.costs.len()];
+        let mut bound = 0;
+        for &p in &state.points {
+            let live = self.live(state, p);
+            if live.iter().all(|&c| !used[c]) {
+                bound += live.iter().map(|&c| self.costs[c]).min().unwrap_or(0);
+                for c in live {
+                    used[c] = true;
+                }
+            }
+        }
+        bound
+    }
+
+    fn solve(&self, mut state: State, best: &mut Option<(u64, Vec<usize>)>) {
+        if !self.reduce(&mut state) {
+            return;
+        }
+        if let Some((best_cost, _)) = *best {
+            if state.cost + self.lower_bound(&state) >= best_cost {
+                return;
+            }
+        }
+        if state.points.is_empty() {
+            *best = Some((state.cost, state.chosen));
+            return;
+        }
+
+        // Branch on the point with the fewest options, cheapest first.
+        let point = *state.points.iter().min_by_key(|&&p| self.live(&state, p).len()).unwrap();
+        let mut options = self.live(&state, point);
+        options.sort_by_key(|&c| self.costs[c]);
+        for cand in options {
+            let mut sub = state.clone();
+            self.choose(&mut sub, cand);
+            self.solve(sub, best);
+            // All covers that use 'cand' have been considered now.
+            state.alive[cand] = false;
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_primes_of(table: u32, arity: u32) -> Vec<Implicant> {
+    let mut primes = Vec::new();
+    {
+        let mut collect = |mask_gap: u32, value: u32, is_prime: bool| {
+            if is_prime {
+                primes.push(Implicant::new(mask_gap, value));
+            }
+        };
+        ::generate(&|v| (table >> v) & 1 == 1, &mut collect, arity);
+    }
+    primes
+}
+
+#[test]
+fn test_select_dc() {
+    // Only 0b11 is required, the rest was a don't-care:
+    let x0 = Implicant::new(0b10, 0b01);
+    let x1 = Implicant::new(0b01, 0b10);
+    let mut costs = CostModel::uniform(2, 1, 0);
+    assert_eq!(Some(vec![x0]), select_cover(&[x0, x1], &[0b11], &costs));
+    costs.positive[0] = 5;
+    assert_eq!(Some(vec![x1]), select_cover(&[x0, x1], &[0b11], &costs));
+    assert_eq!(None, select_cover(&[x0], &[0b00], &costs));
+    assert_eq!(Some(vec![]), select_cover(&[x0], &[], &costs));
+}
+
+#[test]
+fn test_minimize_polarity() {
+    // f = x0 x1' + x1 x2, the consensus term x0 x2 is redundant.
+    let f = |v: u32| ((v & 0b011) == 0b001) || ((v & 0b110) == 0b110);
+    let cover = minimize(&f, 3, &CostModel::terms(3));
+    assert_eq!(vec![Implicant::new(0b001, 0b110), Implicant::new(0b100, 0b001)],
+               cover.implicants);
+
+    // f = x0 x1 with don't-cares at x0 x1' and x0' x1:
+    let on = |v: u32| v == 0b11;
+    let dc = |v: u32| v == 0b01 || v == 0b10;
+    let mut costs = CostModel::uniform(2, 1, 1);
+    costs.positive[1] = 5;
+    let cover = minimize_with_dc(&on, &dc, 2, &costs);
+    assert_eq!(vec![Implicant::new(0b10, 0b01)], cover.implicants);
+    costs.positive[0] = 7;
+    let cover = minimize_with_dc(&on, &dc, 2, &costs);
+    assert_eq!(vec![Implicant::new(0b01, 0b10)], cover.implicants);
+}
+
+#[test]
+fn test_minimize_exhaustive() {
+    let costs = CostModel {
+        positive: vec![1, 3, 2],
+        negative: vec![4, 1, 2],
+        term: 3,
+    };
+    for table in 0..256u32 {
+        let primes = test_primes_of(table, 3);
+        let on_set: Vec<u32> = (0..8).filter(|v| (table >> v) & 1 == 1).collect();
+        let cover = select_cover(&primes, &on_set, &costs).unwrap();
+        for v in 0..8 {
+            assert_eq!((table >> v) & 1 == 1, cover.iter().any(|imp| imp.contains(v)));
+        }
+
+        // Brute force over all subsets of primes:
+        let mut best = u64::MAX;
+        for subset in 0..(1u32 << primes.len()) {
+            let chosen: Vec<Implicant> = (0..primes.len())
+                .filter(|&i| (subset >> i) & 1 == 1)
+                .map(|i| primes[i])
+                .collect();
+            if on_set.iter().all(|&v| chosen.iter().any(|imp| imp.contains(v))) {
+                best = best.min(costs.cost_of_cover(&chosen));
+            }
+        }
+        assert_eq!(best, costs.cost_of_cover(&cover), "table {:08b}", table);
+    }
+}
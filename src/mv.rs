@@ -0,0 +1,316 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multi-valued input variables, e.g. for enumerated states.
+//!
+//! Each variable `i` takes a value in `0..sizes[i]`.  A literal is a set of
+//! values of one variable, stored as bitmask, and a cube is a product of
+//! one literal per variable.  In positional notation like `101 11`, the
+//! `j`-th character of a variable says whether value `j` is in the set.
+//! With only two-valued variables, this is exactly what `generate` does:
+//! `01` stands for `0`, `10` for `1`, and `11` for `-`.
+
+use alloc::collections::BTreeSet;
+use implicant::Implicant;
+use minimize::solve_covering;
+use prelude::*;
+
+/// The number of values of each variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Domain {
+    sizes: Vec<u32>,
+}
+
+impl Domain {
+    /// Each size must be between 1 and 32, and there may be at most
+    /// `2^31` inputs altogether.
+    pub fn new(sizes: Vec<u32>) -> Self {
+        let mut points: u64 = 1;
+        for &size in &sizes {
+            assert!((1..=32).contains(&size), "Unsupported domain size {}", size);
+            points *= size as u64;
+            assert!(points <= 1 << 31, "Too many inputs");
+        }
+        Domain { sizes }
+    }
+
+    /// Only two-valued variables, like for `generate`.
+    pub fn binary(arity: u32) -> Self {
+        Domain::new(vec![2; arity as usize])
+    }
+
+    pub fn sizes(&self) -> &[u32] {
+        &self.sizes
+    }
+
+    /// Number of inputs, i.e. the product of all sizes.
+    pub fn points(&self) -> u32 {
+        self.sizes.iter().product()
+    }
+
+    /// Values of the input with the given index, where variable 0 is the
+    /// least significant digit.  For a binary domain, that's bit order.
+    pub fn decode(&self, mut index: u32) -> Vec<u32> {
+        self.sizes
+            .iter()
+            .map(|&size| {
+                let value = index % size;
+                index /= size;
+                value
+            })
+            .collect()
+    }
+
+    /// Inverse of `decode`.
+    pub fn encode(&self, values: &[u32]) -> u32 {
+        assert_eq!(self.sizes.len(), values.len());
+        self.sizes.iter().zip(values).rev().fold(0, |acc, (&size, &value)| {
+            assert!(value < size, "Value {} out of range {}", value, size);
+            acc * size + value
+        })
+    }
+
+    /// The cube that contains every input.
+    pub fn full(&self) -> MvCube {
+        MvCube { parts: self.sizes.iter().map(|&size| full_part(size)).collect() }
+    }
+}
+
+fn full_part(size: u32) -> u32 {
+    if size == 32 { !0 } else { (1 << size) - 1 }
+}
+
+/// A product of literals, one set of values per variable.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MvCube {
+    /// Bit `j` of `parts[i]` is set if variable `i` may have value `j`.
+    pub parts: Vec<u32>,
+}
+
+impl MvCube {
+    /// The cube containing only the given input.
+    pub fn point(values: &[u32]) -> Self {
+        MvCube { parts: values.iter().map(|&v| 1 << v).collect() }
+    }
+
+    pub fn contains(&self, values: &[u32]) -> bool {
+        self.parts.iter().zip(values).all(|(&part, &v)| part & (1 << v) != 0)
+    }
+
+    pub fn covers(&self, other: &MvCube) -> bool {
+        self.parts.iter().zip(&other.parts).all(|(&big, &small)| small & !big == 0)
+    }
+
+    /// Number of variables that are restricted at all.
+    pub fn literals(&self, domain: &Domain) -> u32 {
+        self.parts
+            .iter()
+            .zip(domain.sizes())
+            .filter(|&(&part, &size)| part != full_part(size))
+            .count() as u32
+    }
+
+    /// Positional notation, e.g. `101 11`.
+    pub fn to_cube_string(&self, domain: &Domain) -> String {
+        let literals: Vec<String> = self.parts
+            .iter()
+            .zip(domain.sizes())
+            .map(|(&part, &size)| {
+                (0..size).map(|j| if part & (1 << j) != 0 { '1' } else { '0' }).collect()
+            })
+            .collect();
+        literals.join(" ")
+    }
+
+    /// The same cube as `Implicant`, if all variables are two-valued.
+    pub fn to_implicant(&self, domain: &Domain) -> Option<Implicant> {
+        if domain.sizes().iter().any(|&size| size != 2) || self.parts.len() > 32 {
+            return None;
+        }
+        let mut imp = Implicant::new(0, 0);
+        for (i, &part) in self.parts.iter().enumerate() {
+            match part {
+                0b01 => {}
+                0b10 => imp.value |= 1 << i,
+                _ => imp.mask_gap |= 1 << i,
+            }
+        }
+        Some(imp)
+    }
+
+    /// All inputs in this cube, as indices for `Domain::decode`.
+    fn indices(&self, domain: &Domain) -> Vec<u32> {
+        let mut indices = vec![0];
+        let mut stride = 1;
+        for (&part, &size) in self.parts.iter().zip(domain.sizes()) {
+            indices = indices
+                .iter()
+                .flat_map(|&base| {
+                    (0..size).filter(move |&j| part & (1 << j) != 0).map(move |j| base + j * stride)
+                })
+                .collect();
+            stride *= size;
+        }
+        indices
+    }
+}
+
+/// Like `generate`, but for multi-valued variables.  Reports each
+/// implicant once, ordered by the number of values beyond one per
+/// variable, and says whether it is prime.
+/// Unlike `generate`, this keeps all implicants in memory.
+pub fn generate(domain: &Domain,
+                sampling_fn: &dyn Fn(&[u32]) -> bool,
+                report_fn: &mut dyn FnMut(&MvCube, bool)) {
+    let table: Vec<bool> = (0..domain.points()).map(|i| sampling_fn(&domain.decode(i))).collect();
+    let mut all: BTreeSet<MvCube> = BTreeSet::new();
+    let mut level: BTreeSet<MvCube> = (0..domain.points())
+        .filter(|&i| table[i as usize])
+        .map(|i| MvCube::point(&domain.decode(i)))
+        .collect();
+
+    while !level.is_empty() {
+        all.extend(level.iter().cloned());
+        // Adding value `v` to variable `i` of an implicant yields an
+        // implicant iff the same cube with only `v` for `i` is one, too.
+        let mut next = BTreeSet::new();
+        let mut primes = Vec::new();
+        for cube in &level {
+            let mut is_prime = true;
+            for (i, &size) in domain.sizes().iter().enumerate() {
+                for v in (0..size).filter(|&v| cube.parts[i] & (1 << v) == 0) {
+                    let mut single = cube.clone();
+                    single.parts[i] = 1 << v;
+                    if all.contains(&single) {
+                        let mut bigger = cube.clone();
+                        bigger.parts[i] |= 1 << v;
+                        next.insert(bigger);
+                        is_prime = false;
+                    }
+                }
+            }
+            primes.push(is_prime);
+        }
+        for (cube, is_prime) in level.iter().zip(primes) {
+            report_fn(cube, is_prime);
+        }
+        level = next;
+    }
+}
+
+/// All prime implicants, sorted.
+pub fn primes(domain: &Domain, sampling_fn: &dyn Fn(&[u32]) -> bool) -> Vec<MvCube> {
+    let mut primes = Vec::new();
+    generate(domain, sampling_fn, &mut |cube, is_prime| if is_prime {
+        primes.push(cube.clone());
+    });
+    primes.sort();
+    primes
+}
+
+/// A cover of `on_fn` with the least number of cubes, and among those,
+/// the least number of literals.  Inputs where `dc_fn` holds may or may
+/// not be covered.
+pub fn minimize(domain: &Domain,
+                on_fn: &dyn Fn(&[u32]) -> bool,
+                dc_fn: &dyn Fn(&[u32]) -> bool)
+                -> Vec<MvCube> {
+    let candidates = primes(domain, &|v| on_fn(v) || dc_fn(v));
+    let weight = domain.sizes().len() as u64 + 1;
+    let costs: Vec<u64> = candidates.iter().map(|c| weight + c.literals(domain) as u64).collect();
+    let mut rows = vec![Vec::new(); domain.points() as usize];
+    for (c, cube) in candidates.iter().enumerate() {
+        for i in cube.indices(domain) {
+            rows[i as usize].push(c);
+        }
+    }
+    let required: Vec<Vec<usize>> = (0..domain.points())
+        .filter(|&i| {
+            let values = domain.decode(i);
+            on_fn(&values) && !dc_fn(&values)
+        })
+        .map(|i| rows[i as usize].clone())
+        .collect();
+    let chosen = solve_covering(&costs, &required).expect("Primes must cover the function");
+    let mut cover: Vec<MvCube> = chosen.into_iter().map(|c| candidates[c].clone()).collect();
+    cover.sort();
+    cover
+}
+
+#[test]
+fn test_domain() {
+    let domain = Domain::new(vec![3, 2, 5]);
+    assert_eq!(30, domain.points());
+    assert_eq!(vec![2, 1, 4], domain.decode(29));
+    assert_eq!(vec![1, 0, 2], domain.decode(13));
+    for i in 0..30 {
+        assert_eq!(i, domain.encode(&domain.decode(i)));
+    }
+    assert_eq!("111 11 11111", domain.full().to_cube_string(&domain));
+    assert_eq!(0, domain.full().literals(&domain));
+}
+
+#[test]
+fn test_binary_matches_generate() {
+    for table in [0x0000_0000u32, 0xffff_ffff, 0x1234_5678, 0xfee1_dead, 0x8000_0001].iter() {
+        let f = |v: u32| table & (1 << v) != 0;
+        let mut expected = Vec::new();
+        ::generate(&f, &mut |m, v, p| expected.push((Implicant::new(m, v), p)), 5);
+        let domain = Domain::binary(5);
+        let mut actual = Vec::new();
+        generate(&domain, &|values| f(domain.encode(values)), &mut |cube, p| {
+            actual.push((cube.to_implicant(&domain).unwrap(), p))
+        });
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual);
+    }
+}
+
+#[test]
+fn test_minimize_states() {
+    // A three-state variable and a binary one: on unless state 1, or if
+    // the binary one is set.  Binary encoding of the state would need more.
+    let domain = Domain::new(vec![3, 2]);
+    let on = |v: &[u32]| v[0] != 1 || v[1] == 1;
+    let expected = vec!["101 11", "111 01"];
+    let primes: Vec<String> =
+        primes(&domain, &on).iter().map(|c| c.to_cube_string(&domain)).collect();
+    assert_eq!(expected, primes);
+    let cover: Vec<String> =
+        minimize(&domain, &on, &|_| false).iter().map(|c| c.to_cube_string(&domain)).collect();
+    assert_eq!(expected, cover);
+
+    // Treating state 1 as don't-care makes it constant.
+    let cover = minimize(&domain, &on, &|v| v[0] == 1);
+    assert_eq!(vec![domain.full()], cover);
+}
+
+#[test]
+fn test_minimize_exhaustive() {
+    // Every function of one 3-valued and one 2-valued variable.
+    let domain = Domain::new(vec![3, 2]);
+    for table in 0..64u32 {
+        let on = |v: &[u32]| table & (1 << domain.encode(v)) != 0;
+        let cover = minimize(&domain, &on, &|_| false);
+        for i in 0..6 {
+            let values = domain.decode(i);
+            assert_eq!(on(&values), cover.iter().any(|c| c.contains(&values)), "{}", table);
+        }
+        let primes = primes(&domain, &on);
+        assert!(cover.iter().all(|c| primes.contains(c)));
+    }
+}
@@ -0,0 +1,425 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reading and writing the Berkeley PLA format, as used by espresso and ABC.
+//!
+//! Column `i` of a cube corresponds to bit `i` of the input, so the leftmost
+//! character is the least significant bit.  To feed output `o` into
+//! `generate`, sample `|v| pla.value(o, v) != Some(false)`, i.e., treat
+//! don't-cares as `true`, and only cover the inputs where `value` is
+//! `Some(true)`.
+
+use std::error;
+use std::fmt;
+use esop::Esop;
+use implicant::{Cover, Implicant};
+use variables::{Variables, VariablesError};
+
+/// Which sets the output part of the cubes describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Type {
+    /// ON-set only.  Everything else is OFF.
+    F,
+    /// ON-set and don't-care set.  Everything else is OFF.  (The default.)
+    Fd,
+    /// ON-set and OFF-set.  Everything else is a don't-care.
+    Fr,
+    /// All three sets.  Anything unspecified is a don't-care.
+    Fdr,
+    /// Exclusive-or sum of products, as used by EXORCISM: the output is
+    /// `1` where an odd number of its `1` cubes contain the input.
+    Esop,
+}
+
+/// A single output character of a cube.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    /// `1` (or `4`)
+    On,
+    /// `0`
+    Off,
+    /// `-` (or `2`)
+    DontCare,
+    /// `~`, i.e., this cube says nothing about this output.
+    Unspecified,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub input: Implicant,
+    pub outputs: Vec<Output>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pla {
+    pub inputs: u32,
+    pub outputs: u32,
+    /// Either empty, or one label per input.
+    pub input_labels: Vec<String>,
+    /// Either empty, or one label per output.
+    pub output_labels: Vec<String>,
+    pub kind: Type,
+    pub rows: Vec<Row>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for ParseError {}
+
+fn parse_count(line: usize, directive: &str, arg: Option<&str>) -> Result<u32, ParseError> {
+    arg.and_then(|s| s.parse().ok()).ok_or_else(|| ParseError {
+        line,
+        message: format!("{} needs a number", directive),
+    })
+}
+
+fn parse_input(line: usize, part: &str) -> Result<Implicant, ParseError> {
+    let mut mask_gap = 0;
+    let mut value = 0;
+    for (i, c) in part.chars().enumerate() {
+        match c {
+            '0' => {}
+            '1' => value |= 1 << i,
+            '-' | '2' => mask_gap |= 1 << i,
+            _ => {
+                return Err(ParseError {
+                    line,
+                    message: format!("invalid input character '{}'", c),
+                })
+            }
+        }
+    }
+    Ok(Implicant::new(mask_gap, value))
+}
+
+fn parse_output(line: usize, part: &str) -> Result<Vec<Output>, ParseError> {
+    part.chars()
+        .map(|c| match c {
+            '1' | '4' => Ok(Output::On),
+            '0' => Ok(Output::Off),
+            '-' | '2' => Ok(Output::DontCare),
+            '~' => Ok(Output::Unspecified),
+            _ => Err(ParseError {
+                line,
+                message: format!("invalid output character '{}'", c),
+            }),
+        })
+        .collect()
+}
+
+impl Pla {
+    pub fn parse(text: &str) -> Result<Pla, ParseError> {
+        let mut inputs = None;
+        let mut outputs = None;
+        let mut pla = Pla {
+            inputs: 0,
+            outputs: 0,
+            input_labels: Vec::new(),
+            output_labels: Vec::new(),
+            kind: Type::Fd,
+            rows: Vec::new(),
+        };
+
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let content = raw.split('#').next().unwrap().trim();
+            if content.is_empty() {
+                continue;
+            }
+            let err = |message: String| Err(ParseError { line, message });
+
+            if content.starts_with('.') {
+                let mut words = content.split_whitespace();
+                let directive = words.next().unwrap();
+                match directive {
+                    ".i" => {
+                        let n = parse_count(line, directive, words.next())?;
+                        if n >= 32 {
+                            return err(format!("can only handle at most 31 inputs, but got {}", n));
+                        }
+                        inputs = Some(n);
+                    }
+                    ".o" => outputs = Some(parse_count(line, directive, words.next())?),
+                    ".ilb" => pla.input_labels = words.map(String::from).collect(),
+                    ".ob" => pla.output_labels = words.map(String::from).collect(),
+                    ".p" => {
+                        parse_count(line, directive, words.next())?;
+                    }
+                    ".type" => {
+                        pla.kind = match words.next() {
+                            Some("f") => Type::F,
+                            Some("fd") => Type::Fd,
+                            Some("fr") => Type::Fr,
+                            Some("fdr") => Type::Fdr,
+                            Some("esop") => Type::Esop,
+                            other => return err(format!("unknown type {:?}", other)),
+                        }
+                    }
+                    ".e" | ".end" => break,
+                    _ => return err(format!("unsupported directive {}", directive)),
+                }
+                continue;
+            }
+
+            let (ni, no) = match (inputs, outputs) {
+                (Some(ni), Some(no)) => (ni as usize, no as usize),
+                _ => return err("cube before .i and .o".to_string()),
+            };
+            let cube: String = content.split_whitespace().collect();
+            // Lengths are in bytes, and `split_at` must not split a character.
+            if let Some(c) = cube.chars().find(|c| !c.is_ascii()) {
+                return err(format!("invalid character '{}'", c));
+            }
+            if cube.len() != ni + no {
+                return err(format!("expected {} input and {} output characters", ni, no));
+            }
+            let (input, output) = cube.split_at(ni);
+            pla.rows.push(Row {
+                input: parse_input(line, input)?,
+                outputs: parse_output(line, output)?,
+            });
+        }
+
+        pla.inputs = inputs.ok_or(ParseError { line: 0, message: "missing .i".to_string() })?;
+        pla.outputs = outputs.ok_or(ParseError { line: 0, message: "missing .o".to_string() })?;
+        if !pla.input_labels.is_empty() && pla.input_labels.len() != pla.inputs as usize {
+            return Err(ParseError { line: 0, message: ".ilb doesn't match .i".to_string() });
+        }
+        if !pla.output_labels.is_empty() && pla.output_labels.len() != pla.outputs as usize {
+            return Err(ParseError { line: 0, message: ".ob doesn't match .o".to_string() });
+        }
+        Ok(pla)
+    }
+
+    /// Builds a type `f` PLA, where output `o` is the disjunction of
+    /// `covers[o]`.  Cubes that appear in several covers share a row.
+    pub fn from_covers(inputs: u32, covers: &[&[Implicant]]) -> Pla {
+        assert!(inputs < 32, "Can only handle at most 31 bits, but tried {} bits", inputs);
+        let mut rows: Vec<Row> = Vec::new();
+        for (o, cover) in covers.iter().enumerate() {
+            for imp in cover.iter() {
+                let index = match rows.iter().position(|row| row.input == *imp) {
+                    Some(index) => index,
+                    None => {
+                        rows.push(Row {
+                            input: *imp,
+                            outputs: vec![Output::Off; covers.len()],
+                        });
+                        rows.len() - 1
+                    }
+                };
+                rows[index].outputs[o] = Output::On;
+            }
+        }
+        Pla {
+            inputs,
+            outputs: covers.len() as u32,
+            input_labels: Vec::new(),
+            output_labels: Vec::new(),
+            kind: Type::F,
+            rows,
+        }
+    }
+
+    /// Builds a type `esop` PLA, where output `o` is the exclusive or of
+    /// `esops[o]`.  Cubes that appear in several ESOPs share a row, so the
+    /// cubes within each ESOP must be distinct, as `esop::minimize` makes them.
+    pub fn from_esops(inputs: u32, esops: &[&Esop]) -> Pla {
+        let covers: Vec<&[Implicant]> = esops.iter().map(|esop| &esop.implicants[..]).collect();
+        let mut pla = Pla::from_covers(inputs, &covers);
+        pla.kind = Type::Esop;
+        pla
+    }
+
+    /// The input labels as active-high variables.  Fails if there are no
+    /// labels, or if they are not unique.
+    pub fn input_variables(&self) -> Result<Variables, VariablesError> {
+        let variables = Variables::from_names(&self.input_labels)?;
+        variables.check_arity(self.inputs)?;
+        Ok(variables)
+    }
+
+    /// All cubes that have the given output character in the given output.
+    pub fn cover(&self, output: u32, which: Output) -> Cover {
+        let implicants = self.rows
+            .iter()
+            .filter(|row| row.outputs[output as usize] == which)
+            .map(|row| row.input)
+            .collect();
+        Cover::new(self.inputs, implicants)
+    }
+
+    /// The value of the given output at the given input,
+    /// or `None` for a don't-care.
+    pub fn value(&self, output: u32, point: u32) -> Option<bool> {
+        let (mut on, mut off, mut dc) = (false, false, false);
+        let mut ons = 0;
+        for row in self.rows.iter().filter(|row| row.input.contains(point)) {
+            match row.outputs[output as usize] {
+                Output::On => {
+                    on = true;
+                    ons += 1;
+                }
+                Output::Off => off = true,
+                Output::DontCare => dc = true,
+                Output::Unspecified => {}
+            }
+        }
+        match self.kind {
+            Type::F => Some(on),
+            Type::Fd => if dc { None } else { Some(on) },
+            Type::Fr => if on { Some(true) } else if off { Some(false) } else { None },
+            Type::Fdr => if dc { None } else if on { Some(true) } else if off { Some(false) } else { None },
+            Type::Esop => Some(ons % 2 == 1),
+        }
+    }
+}
+
+impl fmt::Display for Pla {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, ".i {}", self.inputs)?;
+        writeln!(f, ".o {}", self.outputs)?;
+        if !self.input_labels.is_empty() {
+            writeln!(f, ".ilb {}", self.input_labels.join(" "))?;
+        }
+        if !self.output_labels.is_empty() {
+            writeln!(f, ".ob {}", self.output_labels.join(" "))?;
+        }
+        let kind = match self.kind {
+            Type::F => "f",
+            Type::Fd => "fd",
+            Type::Fr => "fr",
+            Type::Fdr => "fdr",
+            Type::Esop => "esop",
+        };
+        writeln!(f, ".type {}", kind)?;
+        writeln!(f, ".p {}", self.rows.len())?;
+        for row in &self.rows {
+            let input = row.input.to_cube_string(self.inputs);
+            let output: String = row.outputs
+                .iter()
+                .map(|o| match *o {
+                    Output::On => '1',
+                    Output::Off => '0',
+                    Output::DontCare => '-',
+                    Output::Unspecified => '~',
+                })
+                .collect();
+            writeln!(f, "{} {}", input, output)?;
+        }
+        writeln!(f, ".e")
+    }
+}
+
+#[cfg(test)]
+const TEST_PLA: &str = "\
+# Two outputs, with a don't-care
+.i 3
+.o 2
+.ilb a b c
+.ob f g
+.type fd
+.p 3
+1-0 10
+011 11
+111 -1
+.e
+";
+
+#[test]
+fn test_parse() {
+    let pla = Pla::parse(TEST_PLA).unwrap();
+    assert_eq!(3, pla.inputs);
+    assert_eq!(2, pla.outputs);
+    assert_eq!(vec!["a", "b", "c"], pla.input_labels);
+    assert_eq!(vec!["f", "g"], pla.output_labels);
+    assert_eq!(vec!["a", "b", "c"], pla.input_variables().unwrap().names());
+    assert_eq!(Type::Fd, pla.kind);
+    assert_eq!(3, pla.rows.len());
+    // "1-0": a=1, b=gap, c=0
+    assert_eq!(Implicant::new(0b010, 0b001), pla.rows[0].input);
+    assert_eq!(vec![Output::On, Output::Off], pla.rows[0].outputs);
+
+    assert_eq!(Some(true), pla.value(0, 0b001));
+    assert_eq!(Some(true), pla.value(0, 0b011));
+    assert_eq!(Some(true), pla.value(0, 0b110));
+    assert_eq!(None, pla.value(0, 0b111));
+    assert_eq!(Some(false), pla.value(0, 0b000));
+    assert_eq!(Some(true), pla.value(1, 0b110));
+    assert_eq!(Some(true), pla.value(1, 0b111));
+    assert_eq!(Some(false), pla.value(1, 0b001));
+    assert_eq!(vec![Implicant::new(0, 0b111)], pla.cover(0, Output::DontCare).implicants);
+}
+
+#[test]
+fn test_parse_fr() {
+    let pla = Pla::parse(".i 2\n.o 1\n.type fr\n11 1\n0- 0\n").unwrap();
+    assert_eq!(Some(true), pla.value(0, 0b11));
+    assert_eq!(Some(false), pla.value(0, 0b00));
+    assert_eq!(Some(false), pla.value(0, 0b10));
+    assert_eq!(None, pla.value(0, 0b01));
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(1, Pla::parse("01 1\n").unwrap_err().line);
+    assert_eq!(3, Pla::parse(".i 2\n.o 1\n01x 1\n").unwrap_err().line);
+    assert_eq!(3, Pla::parse(".i 2\n.o 1\n0x 1\n").unwrap_err().line);
+    let err = Pla::parse(".i 2\n.o 1\n1é\n").unwrap_err();
+    assert_eq!((3, "invalid character 'é'".to_string()), (err.line, err.message));
+    assert_eq!(1, Pla::parse(".i 32\n").unwrap_err().line);
+    assert_eq!(1, Pla::parse(".mv 3 1 2\n").unwrap_err().line);
+    assert!(Pla::parse(".i 2\n").is_err());
+    assert!(Pla::parse(".i 2\n.o 1\n.ilb a\n").is_err());
+}
+
+#[test]
+fn test_write_roundtrip() {
+    let f = [Implicant::new(0b010, 0b001), Implicant::new(0, 0b110)];
+    let g = [Implicant::new(0, 0b110)];
+    let mut pla = Pla::from_covers(3, &[&f, &g]);
+    assert_eq!(2, pla.rows.len());
+    pla.output_labels = vec!["f".to_string(), "g".to_string()];
+    let text = pla.to_string();
+    assert_eq!(".i 3\n.o 2\n.ob f g\n.type f\n.p 2\n1-0 10\n011 11\n.e\n", text);
+    assert_eq!(pla, Pla::parse(&text).unwrap());
+}
+
+#[test]
+fn test_esop() {
+    // f = a ⊕ b, g = a
+    let f = Esop::new(2, vec![Implicant::new(0b10, 0b01), Implicant::new(0b01, 0b10)]);
+    let g = Esop::new(2, vec![Implicant::new(0b10, 0b01)]);
+    let pla = Pla::from_esops(2, &[&f, &g]);
+    let text = pla.to_string();
+    assert_eq!(".i 2\n.o 2\n.type esop\n.p 2\n1- 11\n-1 10\n.e\n", text);
+    assert_eq!(pla, Pla::parse(&text).unwrap());
+    for v in 0..4 {
+        assert_eq!(Some(f.contains(v)), pla.value(0, v));
+        assert_eq!(Some(g.contains(v)), pla.value(1, v));
+    }
+}
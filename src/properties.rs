@@ -0,0 +1,370 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Structural properties of a function, to help decide how to treat it.
+
+use prelude::*;
+
+/// How the function depends on a single variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unateness {
+    /// The variable doesn't matter at all.
+    Independent,
+    /// Setting the variable never turns the function off.
+    Positive,
+    /// Setting the variable never turns the function on.
+    Negative,
+    /// Both can happen.
+    Binate,
+}
+
+/// `f(x)` is the parity of `x & mask`, inverted if `inverted`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Linear {
+    pub mask: u32,
+    pub inverted: bool,
+}
+
+/// `f(x)` holds iff the sum of `weights[i]` over all set bits `i`
+/// of `x` is at least `threshold`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Threshold {
+    pub weights: Vec<i64>,
+    pub threshold: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Properties {
+    pub arity: u32,
+    /// Bit `i` is set iff variable `i` matters.
+    pub support: u32,
+    /// One entry per variable.
+    pub unateness: Vec<Unateness>,
+    /// Monotonically increasing, i.e., no variable is negative or binate.
+    pub monotone: bool,
+    /// Partition of all variables into classes that can be permuted
+    /// arbitrarily without changing the function.  Ascending.
+    pub symmetry_groups: Vec<Vec<u32>>,
+    /// `f(x) == !f(!x)` for all `x`.
+    pub self_dual: bool,
+    /// Whether the function is an XOR of variables, possibly inverted.
+    pub linear: Option<Linear>,
+    /// Whether the function is a (linear) threshold function, with
+    /// integer weights if so.
+    pub threshold: Option<Threshold>,
+}
+
+/// Samples `sampling_fn` once for each input, and analyzes the result.
+pub fn analyze(sampling_fn: &dyn Fn(u32) -> bool, arity: u32) -> Properties {
+    assert!(arity < 32,
+            "Can only handle at most 31 bits, but tried {} bits",
+            arity);
+    let table: Vec<bool> = (0..(1u32 << arity)).map(sampling_fn).collect();
+    analyze_table(&table)
+}
+
+/// Entry `v` of `table` is the value at input `v`, so the length
+/// must be a power of two.
+pub fn analyze_table(table: &[bool]) -> Properties {
+    assert!(table.len().is_power_of_two() && table.len() <= 1 << 31,
+            "Truth table has length {}, which is not a power of two",
+            table.len());
+    let arity = table.len().trailing_zeros();
+    let f = |v: u32| table[v as usize];
+
+    let unateness: Vec<Unateness> = (0..arity).map(|i| unateness_of(table, i)).collect();
+    let support = (0..arity)
+        .filter(|&i| unateness[i as usize] != Unateness::Independent)
+        .fold(0, |mask, i| mask | (1 << i));
+    let monotone = unateness.iter()
+        .all(|&u| u == Unateness::Positive || u == Unateness::Independent);
+    let all = (table.len() - 1) as u32;
+    let self_dual = (0..=all).all(|v| f(v) != f(!v & all));
+
+    Properties {
+        arity,
+        support,
+        monotone,
+        symmetry_groups: symmetry_groups(table, arity),
+        self_dual,
+        linear: linear(table, arity),
+        threshold: threshold(table, arity, &unateness),
+        unateness,
+    }
+}
+
+fn unateness_of(table: &[bool], i: u32) -> Unateness {
+    let bit = 1 << i;
+    let mut rises = false;
+    let mut falls = false;
+    for v in (0..table.len()).filter(|v| v & bit == 0) {
+        match (table[v], table[v | bit]) {
+            (false, true) => rises = true,
+            (true, false) => falls = true,
+            _ => {}
+        }
+    }
+    match (rises, falls) {
+        (false, false) => Unateness::Independent,
+        (true, false) => Unateness::Positive,
+        (false, true) => Unateness::Negative,
+        (true, true) => Unateness::Binate,
+    }
+}
+
+fn symmetric(table: &[bool], i: u32, j: u32) -> bool {
+    let (bi, bj) = (1 << i, 1 << j);
+    (0..table.len())
+        .filter(|v| v & bi != 0 && v & bj == 0)
+        .all(|v| table[v] == table[v ^ bi ^ bj])
+}
+
+fn symmetry_groups(table: &[bool], arity: u32) -> Vec<Vec<u32>> {
+    // Symmetry in pairs is an equivalence relation, so comparing with the
+    // first member of each group suffices.
+    let mut groups: Vec<Vec<u32>> = Vec::new();
+    for i in 0..arity {
+        match groups.iter_mut().find(|group| symmetric(table, group[0], i)) {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+    groups
+}
+
+fn linear(table: &[bool], arity: u32) -> Option<Linear> {
+    let inverted = table[0];
+    let mask = (0..arity)
+        .filter(|&i| table[1 << i] != inverted)
+        .fold(0, |mask, i| mask | (1 << i));
+    let matches = (0..table.len())
+        .all(|v| table[v] == (((v as u32 & mask).count_ones() % 2 == 1) != inverted));
+    if matches {
+        Some(Linear { mask, inverted })
+    } else {
+        None
+    }
+}
+
+fn threshold(table: &[bool], arity: u32, unateness: &[Unateness]) -> Option<Threshold> {
+    if unateness.contains(&Unateness::Binate) {
+        return None;
+    }
+    if table.iter().all(|&b| !b) {
+        return Some(Threshold { weights: vec![0; arity as usize], threshold: 1 });
+    }
+    if table.iter().all(|&b| b) {
+        return Some(Threshold { weights: vec![0; arity as usize], threshold: 0 });
+    }
+
+    // Complementing the negative variables yields a monotone function g,
+    // which is fully described by its minimal ON and maximal OFF points.
+    let flip = (0..arity)
+        .filter(|&i| unateness[i as usize] == Unateness::Negative)
+        .fold(0, |mask, i| mask | (1 << i));
+    let g = |v: u32| table[(v ^ flip) as usize];
+    let all = (table.len() - 1) as u32;
+    let min_on: Vec<u32> = (0..=all)
+        .filter(|&v| g(v) && (0..arity).all(|i| v & (1 << i) == 0 || !g(v ^ (1 << i))))
+        .collect();
+    let max_off: Vec<u32> = (0..=all)
+        .filter(|&v| !g(v) && (0..arity).all(|i| v & (1 << i) != 0 || g(v | (1 << i))))
+        .collect();
+
+    let weights = monotone_weights(arity, &min_on, &max_off)?;
+    let sum = |w: &[i64], v: u32| -> i64 {
+        (0..arity).filter(|&i| v & (1 << i) != 0).map(|i| w[i as usize]).sum()
+    };
+    let mut threshold = min_on.iter().map(|&v| sum(&weights, v)).min().unwrap();
+
+    // Undo the complement: w·(1 - x) = w - w·x.
+    let mut signed = weights;
+    for i in (0..arity).filter(|&i| flip & (1 << i) != 0) {
+        threshold -= signed[i as usize];
+        signed[i as usize] = -signed[i as usize];
+    }
+    Some(Threshold { weights: signed, threshold })
+}
+
+/// Non-negative integer weights that put every point of `min_on` strictly
+/// above every point of `max_off`, if such weights exist.
+fn monotone_weights(arity: u32, min_on: &[u32], max_off: &[u32]) -> Option<Vec<i64>> {
+    // Variables: the weights, the threshold t, and the margin d.
+    // Maximize d subject to w·x - t >= d on min_on, w·y - t <= 0 on
+    // max_off, and sum(w) + t <= 1.
+    let n = arity as usize;
+    let row = |v: u32, sign: f64, t: f64, d: f64| -> Vec<f64> {
+        let mut row: Vec<f64> = (0..n).map(|i| if v & (1 << i) != 0 { sign } else { 0.0 }).collect();
+        row.push(t);
+        row.push(d);
+        row
+    };
+    let mut a: Vec<Vec<f64>> = Vec::new();
+    a.extend(min_on.iter().map(|&v| row(v, -1.0, 1.0, 1.0)));
+    a.extend(max_off.iter().map(|&v| row(v, 1.0, -1.0, 0.0)));
+    a.push(row(!0, 1.0, 1.0, 0.0));
+    let mut b = vec![0.0; a.len()];
+    b[a.len() - 1] = 1.0;
+    let mut c = vec![0.0; n + 2];
+    c[n + 1] = 1.0;
+
+    let z = simplex(&a, &b, &c)?;
+    let margin = z[n + 1];
+    if margin < 1e-9 {
+        return None;
+    }
+
+    // Scaled to margin 1, rounding to multiples of 1/(n+1) can't break it,
+    // but smaller scales often work, too.
+    let sum = |w: &[i64], v: u32| -> i64 {
+        (0..n).filter(|&i| v & (1 << i) != 0).map(|i| w[i]).sum()
+    };
+    (1..=(n as u64 + 1)).map(|k| {
+        (0..n).map(|i| round(z[i] / margin * k as f64)).collect::<Vec<i64>>()
+    }).find(|w| {
+        let low = min_on.iter().map(|&v| sum(w, v)).min().unwrap();
+        max_off.iter().all(|&v| sum(w, v) < low)
+    })
+}
+
+/// `f64::round` needs `std`.
+fn round(x: f64) -> i64 {
+    if x < 0.0 {
+        (x - 0.5) as i64
+    } else {
+        (x + 0.5) as i64
+    }
+}
+
+/// Maximizes `c·z` subject to `a·z <= b` and `z >= 0`, where `b >= 0`.
+/// Plain dense tableau with Bland's rule.  Returns `None` if unbounded.
+fn simplex(a: &[Vec<f64>], b: &[f64], c: &[f64]) -> Option<Vec<f64>> {
+    const EPS: f64 = 1e-9;
+    let m = a.len();
+    let n = c.len();
+    let width = n + m + 1;
+    let mut tableau = vec![vec![0.0; width]; m + 1];
+    for i in 0..m {
+        tableau[i][..n].copy_from_slice(&a[i]);
+        tableau[i][n + i] = 1.0;
+        tableau[i][width - 1] = b[i];
+    }
+    for j in 0..n {
+        tableau[m][j] = -c[j];
+    }
+    let mut basis: Vec<usize> = (n..n + m).collect();
+
+    while let Some(enter) = (0..n + m).find(|&j| tableau[m][j] < -EPS) {
+        let mut leave: Option<(usize, f64)> = None;
+        for i in 0..m {
+            if tableau[i][enter] <= EPS {
+                continue;
+            }
+            let ratio = tableau[i][width - 1] / tableau[i][enter];
+            let better = match leave {
+                None => true,
+                Some((l, best)) => ratio < best - EPS || (ratio < best + EPS && basis[i] < basis[l]),
+            };
+            if better {
+                leave = Some((i, ratio));
+            }
+        }
+        let (pivot_row, _) = leave?;
+
+        let pivot = tableau[pivot_row][enter];
+        for x in tableau[pivot_row].iter_mut() {
+            *x /= pivot;
+        }
+        let pivot_values = tableau[pivot_row].clone();
+        for (i, row) in tableau.iter_mut().enumerate() {
+            if i == pivot_row || row[enter] == 0.0 {
+                continue;
+            }
+            let factor = row[enter];
+            for (x, p) in row.iter_mut().zip(&pivot_values) {
+                *x -= factor * p;
+            }
+        }
+        basis[pivot_row] = enter;
+    }
+
+    let mut z = vec![0.0; n];
+    for (i, &var) in basis.iter().enumerate() {
+        if var < n {
+            z[var] = tableau[i][width - 1];
+        }
+    }
+    Some(z)
+}
+
+#[test]
+fn test_majority() {
+    let maj = analyze(&|v| v.count_ones() >= 2, 3);
+    assert_eq!(Properties {
+                   arity: 3,
+                   support: 0b111,
+                   unateness: vec![Unateness::Positive; 3],
+                   monotone: true,
+                   symmetry_groups: vec![vec![0, 1, 2]],
+                   self_dual: true,
+                   linear: None,
+                   threshold: Some(Threshold { weights: vec![1, 1, 1], threshold: 2 }),
+               },
+               maj);
+}
+
+#[test]
+fn test_xor_and_mixed() {
+    let xor = analyze(&|v| v.count_ones() % 2 == 1, 3);
+    assert_eq!(vec![Unateness::Binate; 3], xor.unateness);
+    assert!(!xor.monotone);
+    assert!(xor.self_dual);
+    assert_eq!(Some(Linear { mask: 0b111, inverted: false }), xor.linear);
+    assert_eq!(None, xor.threshold);
+
+    // x0 x1', where x2 doesn't matter.
+    let table: Vec<bool> = (0..8).map(|v| v & 0b011 == 0b001).collect();
+    let props = analyze_table(&table);
+    assert_eq!(0b011, props.support);
+    assert_eq!(vec![Unateness::Positive, Unateness::Negative, Unateness::Independent],
+               props.unateness);
+    assert!(!props.monotone);
+    assert_eq!(vec![vec![0], vec![1], vec![2]], props.symmetry_groups);
+    assert!(!props.self_dual);
+    assert_eq!(None, props.linear);
+    assert_eq!(Some(Threshold { weights: vec![1, -1, 0], threshold: 1 }), props.threshold);
+
+    let zero = analyze(&|_| false, 2);
+    assert_eq!(Some(Linear { mask: 0, inverted: false }), zero.linear);
+    assert_eq!(vec![vec![0, 1]], zero.symmetry_groups);
+}
+
+#[test]
+fn test_threshold_exhaustive() {
+    // There are exactly 104 threshold functions of 3 variables.
+    let mut count = 0;
+    for table in 0..256u32 {
+        let f = |v: u32| (table >> v) & 1 == 1;
+        if let Some(t) = analyze(&f, 3).threshold {
+            count += 1;
+            for v in 0..8 {
+                let sum: i64 = (0..3).filter(|&i| v & (1 << i) != 0).map(|i| t.weights[i]).sum();
+                assert_eq!(f(v as u32), sum >= t.threshold, "table {:08b}", table);
+            }
+        }
+    }
+    assert_eq!(104, count);
+}
@@ -0,0 +1,189 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reed–Muller expansions: every function is the exclusive or of a unique
+//! set of monomials, once it is fixed which variables appear complemented
+//! (the polarity).  With no variable complemented, this is the algebraic
+//! normal form (ANF).
+//!
+//! Monomials are returned as cubes of an `Esop`: variable `i` of the
+//! monomial is a `1` in the cube, or a `0` if bit `i` of the polarity is
+//! set.  All other positions are gaps.
+
+use esop::Esop;
+use implicant::{low_mask, Implicant};
+use masked_count;
+use prelude::*;
+#[cfg(test)]
+use test_random::Rng;
+
+/// The ANF of `sampling_fn`, which is called once for each input.
+pub fn anf(sampling_fn: &dyn Fn(u32) -> bool, arity: u32) -> Esop {
+    fixed_polarity(sampling_fn, arity, 0)
+}
+
+/// Entry `v` of `table` is the value at input `v`, so the length
+/// must be a power of two.
+pub fn anf_table(table: &[bool]) -> Esop {
+    fixed_polarity_table(table, 0)
+}
+
+/// The Reed–Muller form of `sampling_fn` in which exactly the variables
+/// in `polarity` are complemented.  Calls `sampling_fn` once for each input.
+pub fn fixed_polarity(sampling_fn: &dyn Fn(u32) -> bool, arity: u32, polarity: u32) -> Esop {
+    fixed_polarity_table(&table_of(sampling_fn, arity), polarity)
+}
+
+/// Like `fixed_polarity`, for a truth table like in `anf_table`.
+pub fn fixed_polarity_table(table: &[bool], polarity: u32) -> Esop {
+    let arity = arity_of(table);
+    assert_eq!(0, polarity & !low_mask(arity),
+               "Polarity {:b} uses more than {} variables", polarity, arity);
+    // Substituting `x ^ polarity` complements exactly those variables.
+    let mut coefficients: Vec<bool> = (0..table.len())
+        .map(|v| table[v ^ polarity as usize])
+        .collect();
+    moebius(&mut coefficients);
+    to_esop(&coefficients, arity, polarity)
+}
+
+/// The polarity with the fewest monomials (and among those, the fewest
+/// literals), and the form for it.  Ties go to the numerically smallest
+/// polarity.  This tries all polarities, so it takes time proportional
+/// to `arity · 4^arity`.
+pub fn best_polarity(sampling_fn: &dyn Fn(u32) -> bool, arity: u32) -> (u32, Esop) {
+    best_polarity_table(&table_of(sampling_fn, arity))
+}
+
+/// Like `best_polarity`, for a truth table like in `anf_table`.
+pub fn best_polarity_table(table: &[bool]) -> (u32, Esop) {
+    let arity = arity_of(table);
+    let mut best = (0, fixed_polarity_table(table, 0));
+    for polarity in 1..(1u32 << arity) {
+        let candidate = fixed_polarity_table(table, polarity);
+        if (candidate.len(), candidate.literals()) < (best.1.len(), best.1.literals()) {
+            best = (polarity, candidate);
+        }
+    }
+    best
+}
+
+/// A single ANF coefficient: whether the product of the variables in
+/// `monomial` appears in the ANF.  This only samples the inputs inside
+/// `monomial`, so it also works for functions too wide for a truth table.
+pub fn coefficient(sampling_fn: &dyn Fn(u32) -> bool, monomial: u32) -> bool {
+    masked_count::up(monomial).filter(|&v| sampling_fn(v)).count() % 2 == 1
+}
+
+/// The largest number of variables in a monomial of `form`, which is the
+/// same for every polarity.  `None` for the constant `0`.
+pub fn degree(form: &Esop) -> Option<u32> {
+    form.iter().map(|imp| imp.literals(form.arity)).max()
+}
+
+fn table_of(sampling_fn: &dyn Fn(u32) -> bool, arity: u32) -> Vec<bool> {
+    assert!(arity < 32,
+            "Can only handle at most 31 bits, but tried {} bits",
+            arity);
+    (0..(1u32 << arity)).map(sampling_fn).collect()
+}
+
+fn arity_of(table: &[bool]) -> u32 {
+    assert!(table.len().is_power_of_two() && table.len() <= 1 << 31,
+            "Truth table has length {}, which is not a power of two",
+            table.len());
+    table.len().trailing_zeros()
+}
+
+/// In place, turns a truth table into ANF coefficients: afterwards, entry
+/// `m` is the parity of the table over all subsets of `m`.
+fn moebius(table: &mut [bool]) {
+    let mut bit = 1;
+    while bit < table.len() {
+        for v in 0..table.len() {
+            if v & bit != 0 {
+                table[v] ^= table[v ^ bit];
+            }
+        }
+        bit <<= 1;
+    }
+}
+
+fn to_esop(coefficients: &[bool], arity: u32, polarity: u32) -> Esop {
+    let all = low_mask(arity);
+    let monomials = (0..coefficients.len() as u32)
+        .filter(|&m| coefficients[m as usize])
+        .map(|m| Implicant::new(all & !m, m & !polarity))
+        .collect();
+    Esop::new(arity, monomials)
+}
+
+#[test]
+fn test_anf_small() {
+    // and, xor, or
+    let and = anf(&|v| v == 0b11, 2);
+    assert_eq!(vec![Implicant::new(0, 0b11)], and.implicants);
+    let xor = anf(&|v| v == 0b01 || v == 0b10, 2);
+    assert_eq!(vec![Implicant::new(0b10, 0b01), Implicant::new(0b01, 0b10)], xor.implicants);
+    assert_eq!(Some(1), degree(&xor));
+    let or = anf_table(&[false, true, true, true]);
+    assert_eq!(3, or.len());
+    assert_eq!(Some(2), degree(&or));
+    assert_eq!(None, degree(&anf(&|_| false, 3)));
+    assert_eq!(vec![Implicant::new(0b111, 0)], anf(&|_| true, 3).implicants);
+}
+
+#[test]
+fn test_polarity() {
+    // nor = a'·b', but 1 ⊕ a ⊕ b ⊕ a·b in ANF
+    let nor = [true, false, false, false];
+    assert_eq!(4, anf_table(&nor).len());
+    // a'·(1 ⊕ b) = a' ⊕ a'·b
+    assert_eq!(vec![Implicant::new(0b10, 0), Implicant::new(0, 0b10)],
+               fixed_polarity_table(&nor, 0b01).implicants);
+    let (polarity, best) = best_polarity_table(&nor);
+    assert_eq!(0b11, polarity);
+    assert_eq!(vec![Implicant::new(0, 0)], best.implicants);
+}
+
+#[test]
+fn test_random_functions() {
+    use properties;
+    let mut rng = Rng::new(0x0bad_cafe);
+    for arity in 0..7 {
+        for _ in 0..6 {
+            let table = rng.table();
+            let f = |v: u32| (table >> v) & 1 == 1;
+            let form = anf(&f, arity);
+            let (polarity, best) = best_polarity(&f, arity);
+            assert!(best.len() <= form.len());
+            for p in 0..(1u32 << arity) {
+                let other = fixed_polarity(&f, arity, p);
+                assert_eq!(degree(&form), degree(&other));
+                assert!(best.len() <= other.len());
+                for v in 0..(1u32 << arity) {
+                    assert_eq!(f(v), other.contains(v));
+                }
+            }
+            assert_eq!(fixed_polarity(&f, arity, polarity), best);
+            for m in 0..(1u32 << arity) {
+                assert_eq!(form.iter().any(|imp| imp.value == m), coefficient(&f, m));
+            }
+            let linear = properties::analyze(&f, arity).linear.is_some();
+            assert_eq!(linear, degree(&form) <= Some(1));
+        }
+    }
+}
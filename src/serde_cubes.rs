@@ -0,0 +1,61 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compact representation of a `Cover` as cube strings, for use with
+//! `#[serde(with = "implicants::serde_cubes")]`:
+//!
+//! ```text
+//! {"arity": 3, "cubes": ["1-0", "-11"]}
+//! ```
+//!
+//! Character `i` stands for bit `i`, like in `Implicant::to_cube_string`.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use implicant::{Cover, Implicant};
+use prelude::*;
+
+#[derive(Serialize, Deserialize)]
+struct Compact {
+    arity: u32,
+    cubes: Vec<String>,
+}
+
+pub fn serialize<S: Serializer>(cover: &Cover, serializer: S) -> Result<S::Ok, S::Error> {
+    Compact {
+        arity: cover.arity,
+        cubes: cover.iter().map(|imp| imp.to_cube_string(cover.arity)).collect(),
+    }
+    .serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Cover, D::Error> {
+    let compact = Compact::deserialize(deserializer)?;
+    let implicants = compact
+        .cubes
+        .iter()
+        .map(|cube| {
+            if cube.len() != compact.arity as usize {
+                return Err(D::Error::custom(format!("Cube '{}' should have {} characters",
+                                                    cube,
+                                                    compact.arity)));
+            }
+            Implicant::from_cube_string(cube)
+                .ok_or_else(|| D::Error::custom(format!("Invalid cube '{}'", cube)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Cover::new(compact.arity, implicants))
+}
@@ -0,0 +1,88 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reproducible random functions and covers for the tests.
+
+use implicant::{wide_mask, Cover, Implicant};
+use prelude::*;
+use unate::{WideCover, WideImplicant};
+
+/// A linear congruential generator, which is plenty for test inputs.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    /// The upper half of the next state, as the lower bits are weak.
+    pub fn next(&mut self) -> u32 {
+        self.0 = self.0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 32) as u32
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: u32) -> u32 {
+        self.next() % n
+    }
+
+    /// A function of up to 6 inputs, where bit `v` is the value at input `v`.
+    pub fn table(&mut self) -> u64 {
+        (u64::from(self.next()) << 32) | u64::from(self.next())
+    }
+
+    /// `len` cubes, where each position is a gap, `0` or `1` equally often.
+    pub fn cover(&mut self, arity: u32, len: usize) -> Cover {
+        let implicants = (0..len)
+            .map(|_| {
+                let (mut mask_gap, mut value) = (0, 0);
+                for i in 0..arity {
+                    match self.below(3) {
+                        0 => mask_gap |= 1 << i,
+                        1 => value |= 1 << i,
+                        _ => {}
+                    }
+                }
+                Implicant::new(mask_gap, value)
+            })
+            .collect();
+        Cover::new(arity, implicants)
+    }
+
+    /// `len` cubes of up to 64 positions, each with at most `literals`
+    /// literals, as wide cubes with a literal almost everywhere are useless.
+    pub fn wide_cover(&mut self, arity: u32, len: usize, literals: u32) -> WideCover {
+        let implicants = (0..len)
+            .map(|_| {
+                let mut imp = WideImplicant::new(wide_mask(arity), 0);
+                for _ in 0..literals {
+                    let bit = 1 << self.below(arity);
+                    imp.mask_gap &= !bit;
+                    imp.value = (imp.value & !bit) | if self.below(2) == 0 { bit } else { 0 };
+                }
+                imp
+            })
+            .collect();
+        WideCover::new(arity, implicants)
+    }
+}
+
+/// Whether `cover` contains each input, so only for a few variables.
+pub fn wide_table(cover: &WideCover) -> Vec<bool> {
+    (0..(1u64 << cover.arity)).map(|p| cover.contains(p)).collect()
+}
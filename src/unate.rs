@@ -0,0 +1,312 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reasoning about covers over up to 64 variables without evaluating them
+//! on every input, by the unate recursive paradigm: split on the most
+//! binate variable until the cofactors are unate, where the answer is easy.
+
+use implicant::{wide_mask, Cover, Implicant};
+use prelude::*;
+#[cfg(test)]
+use test_random::{self, Rng};
+
+/// Like `Implicant`, but with room for 64 variables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WideImplicant {
+    pub mask_gap: u64,
+    pub value: u64,
+}
+
+impl WideImplicant {
+    pub fn new(mask_gap: u64, value: u64) -> Self {
+        assert_eq!(0, mask_gap & value,
+                   "Gaps {:b} and value {:b} overlap", mask_gap, value);
+        WideImplicant { mask_gap, value }
+    }
+
+    pub fn contains(&self, point: u64) -> bool {
+        (point & !self.mask_gap) == self.value
+    }
+
+    fn conflicts(&self, other: &WideImplicant) -> u64 {
+        (self.value ^ other.value) & !self.mask_gap & !other.mask_gap
+    }
+}
+
+impl From<Implicant> for WideImplicant {
+    fn from(imp: Implicant) -> Self {
+        WideImplicant::new(imp.mask_gap as u64, imp.value as u64)
+    }
+}
+
+/// Like `Cover`, but with room for 64 variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WideCover {
+    pub arity: u32,
+    pub implicants: Vec<WideImplicant>,
+}
+
+impl<'a> From<&'a Cover> for WideCover {
+    fn from(cover: &'a Cover) -> Self {
+        WideCover::new(cover.arity, cover.iter().map(|&imp| imp.into()).collect())
+    }
+}
+
+impl WideCover {
+    pub fn new(arity: u32, implicants: Vec<WideImplicant>) -> Self {
+        let arity_mask = wide_mask(arity);
+        for imp in &implicants {
+            assert_eq!(0, imp.value & !arity_mask, "Literal beyond arity {}", arity);
+        }
+        WideCover { arity, implicants }
+    }
+
+    pub fn contains(&self, point: u64) -> bool {
+        self.implicants.iter().any(|imp| imp.contains(point))
+    }
+
+    /// Back to a `Cover`, if the arity allows.
+    pub fn to_cover(&self) -> Option<Cover> {
+        if self.arity > 32 {
+            return None;
+        }
+        let implicants = self.implicants
+            .iter()
+            .map(|imp| Implicant::new(imp.mask_gap as u32, imp.value as u32))
+            .collect();
+        Some(Cover::new(self.arity, implicants))
+    }
+
+    /// Whether every input is contained.
+    pub fn is_tautology(&self) -> bool {
+        tautology(self.implicants.clone(), wide_mask(self.arity))
+    }
+
+    /// Whether every input of `cube` is contained.
+    pub fn contains_cube(&self, cube: &WideImplicant) -> bool {
+        self.cofactor(cube).is_tautology()
+    }
+
+    /// A cover of exactly the inputs that are not contained.
+    pub fn complement(&self) -> WideCover {
+        let implicants = complement(&self.implicants, wide_mask(self.arity));
+        WideCover::new(self.arity, implicants)
+    }
+
+    /// Like `Cover::cofactor`.
+    pub fn cofactor(&self, cube: &WideImplicant) -> WideCover {
+        let implicants = cofactor(&self.implicants, wide_mask(self.arity) & !cube.mask_gap, cube);
+        WideCover::new(self.arity, implicants)
+    }
+}
+
+fn cofactor(cubes: &[WideImplicant], fixed: u64, cube: &WideImplicant) -> Vec<WideImplicant> {
+    cubes.iter()
+        .filter(|imp| imp.conflicts(cube) == 0)
+        .map(|imp| WideImplicant::new(imp.mask_gap | fixed, imp.value & !fixed))
+        .collect()
+}
+
+/// How often each variable occurs as positive and as negative literal.
+fn column_counts(cubes: &[WideImplicant], arity_mask: u64) -> Vec<(u32, u32)> {
+    let mut counts = vec![(0, 0); 64];
+    for imp in cubes {
+        let literals = !imp.mask_gap & arity_mask;
+        for (i, count) in counts.iter_mut().enumerate() {
+            if literals & (1 << i) != 0 {
+                if imp.value & (1 << i) != 0 {
+                    count.0 += 1;
+                } else {
+                    count.1 += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+/// The variable to split on: occurs in both polarities as often as
+/// possible, or failing that, at all as often as possible.
+fn split_variable(counts: &[(u32, u32)]) -> Option<usize> {
+    (0..counts.len())
+        .filter(|&i| counts[i] != (0, 0))
+        .max_by_key(|&i| {
+            let (pos, neg) = counts[i];
+            (pos.min(neg), pos + neg)
+        })
+}
+
+fn cofactor_literal(cubes: &[WideImplicant], var: usize, value: bool) -> Vec<WideImplicant> {
+    let bit = 1 << var;
+    let cube = WideImplicant::new(!bit, if value { bit } else { 0 });
+    cofactor(cubes, bit, &cube)
+}
+
+fn tautology(mut cubes: Vec<WideImplicant>, arity_mask: u64) -> bool {
+    loop {
+        if cubes.iter().any(|imp| imp.mask_gap & arity_mask == arity_mask) {
+            return true;
+        }
+        if cubes.is_empty() {
+            return false;
+        }
+        // Too few inputs to cover everything, even without overlap.
+        let volume: u128 = cubes.iter()
+            .map(|imp| 1u128 << (imp.mask_gap & arity_mask).count_ones())
+            .sum();
+        if volume < 1u128 << arity_mask.count_ones() {
+            return false;
+        }
+        // A unate variable doesn't help: the cofactor against the opposite
+        // literal is the smaller one, and consists of the cubes without it.
+        let counts = column_counts(&cubes, arity_mask);
+        let unate = (0..64).filter(|&i| (counts[i].0 == 0) != (counts[i].1 == 0))
+                           .fold(0u64, |acc, i| acc | (1 << i));
+        if unate == 0 {
+            break;
+        }
+        cubes.retain(|imp| !imp.mask_gap & unate == 0);
+    }
+    let counts = column_counts(&cubes, arity_mask);
+    let var = split_variable(&counts).unwrap();
+    tautology(cofactor_literal(&cubes, var, true), arity_mask) &&
+        tautology(cofactor_literal(&cubes, var, false), arity_mask)
+}
+
+fn complement(cubes: &[WideImplicant], arity_mask: u64) -> Vec<WideImplicant> {
+    if cubes.is_empty() {
+        return vec![WideImplicant::new(arity_mask, 0)];
+    }
+    if cubes.iter().any(|imp| imp.mask_gap & arity_mask == arity_mask) {
+        return Vec::new();
+    }
+    // Literals that all cubes share form a common cube `c`, so that the
+    // cover is `c G`, and its complement is `!c + !G`.  For a single cube,
+    // `G` is a tautology, so this is just De Morgan.
+    let common = cubes.iter().fold(arity_mask, |acc, imp| {
+        acc & !imp.mask_gap & !(imp.value ^ cubes[0].value)
+    });
+    if common != 0 {
+        let mut result: Vec<WideImplicant> = (0..64)
+            .map(|i| 1u64 << i)
+            .filter(|&bit| common & bit != 0)
+            .map(|bit| WideImplicant::new(arity_mask & !bit, !cubes[0].value & bit))
+            .collect();
+        let rest: Vec<WideImplicant> = cubes.iter()
+            .map(|imp| WideImplicant::new(imp.mask_gap | common, imp.value & !common))
+            .collect();
+        result.extend(complement(&rest, arity_mask));
+        remove_contained(&mut result);
+        return result;
+    }
+    let counts = column_counts(cubes, arity_mask);
+    let var = split_variable(&counts).unwrap();
+    let bit = 1u64 << var;
+    let high = complement(&cofactor_literal(cubes, var, true), arity_mask);
+    let low = complement(&cofactor_literal(cubes, var, false), arity_mask);
+
+    // Cubes in both halves don't need the literal.
+    let mut result: Vec<WideImplicant> = Vec::new();
+    for imp in &high {
+        if low.contains(imp) {
+            result.push(*imp);
+        } else {
+            result.push(WideImplicant::new(imp.mask_gap & !bit, imp.value | bit));
+        }
+    }
+    for imp in &low {
+        if !high.contains(imp) {
+            result.push(WideImplicant::new(imp.mask_gap & !bit, imp.value));
+        }
+    }
+    remove_contained(&mut result);
+    result
+}
+
+fn covers(big: &WideImplicant, small: &WideImplicant) -> bool {
+    (small.mask_gap & !big.mask_gap) == 0 && (small.value & !big.mask_gap) == big.value
+}
+
+fn remove_contained(cubes: &mut Vec<WideImplicant>) {
+    cubes.sort_by_key(|imp| !imp.mask_gap.count_ones());
+    let mut kept: Vec<WideImplicant> = Vec::with_capacity(cubes.len());
+    for imp in cubes.drain(..) {
+        if !kept.iter().any(|big| covers(big, &imp)) {
+            kept.push(imp);
+        }
+    }
+    *cubes = kept;
+}
+
+#[test]
+fn test_against_truth_table() {
+    let mut rng = Rng::new(1);
+    for arity in 1..7 {
+        for len in 0..8 {
+            let cover = rng.wide_cover(arity, len, arity / 2 + 1);
+            let table = test_random::wide_table(&cover);
+            assert_eq!(table.iter().all(|&b| b), cover.is_tautology(), "{:?}", cover);
+            let complement = cover.complement();
+            assert!(table.iter().zip(test_random::wide_table(&complement)).all(|(&a, b)| a != b),
+                    "{:?} vs {:?}", cover, complement);
+            let cube = rng.wide_cover(arity, 1, 2).implicants[0];
+            let expected = (0..(1u64 << arity)).all(|p| !cube.contains(p) || table[p as usize]);
+            assert_eq!(expected, cover.contains_cube(&cube));
+        }
+    }
+}
+
+#[test]
+fn test_64_variables() {
+    // x0 + x0' x1 + x0' x1' x2 + ... + x0' ... x63' covers everything, once.
+    let chain: Vec<WideImplicant> = (0..65)
+        .map(|i: u32| {
+            let fixed = if i == 64 { !0 } else { (1u64 << i) | ((1u64 << i) - 1) };
+            let value = if i == 64 { 0 } else { 1 << i };
+            WideImplicant::new(!fixed, value)
+        })
+        .collect();
+    let all = WideCover::new(64, chain.clone());
+    assert!(all.is_tautology());
+    assert!(all.complement().implicants.is_empty());
+
+    let most = WideCover::new(64, chain[..64].to_vec());
+    assert!(!most.is_tautology());
+    assert_eq!(vec![WideImplicant::new(0, 0)], most.complement().implicants);
+    assert!(most.contains_cube(&WideImplicant::new(!0 >> 1, 1 << 63)));
+    assert!(!most.contains_cube(&WideImplicant::new(!0 >> 1, 0)));
+
+    // Random covers are far from tautologies, but need some work to refute.
+    let mut rng = Rng::new(2);
+    let cover = rng.wide_cover(64, 200, 3);
+    assert!(!cover.is_tautology());
+    let cover = rng.wide_cover(64, 12, 3);
+    let complement = cover.complement();
+    for imp in &complement.implicants {
+        assert!(cover.implicants.iter().all(|c| c.conflicts(imp) != 0));
+    }
+    let mut both = cover.clone();
+    both.implicants.extend(complement.implicants);
+    assert!(both.is_tautology());
+}
+
+#[test]
+fn test_narrow() {
+    let cover = Cover::new(3, vec![Implicant::new(0b010, 0b100)]);
+    let wide = WideCover::from(&cover);
+    assert_eq!(Some(cover), wide.to_cover());
+    assert_eq!(None, WideCover::new(33, vec![]).to_cover());
+}
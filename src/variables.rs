@@ -0,0 +1,203 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Names for the bits of the input, so that results can be read and
+//! exported without mapping bit positions back by hand.
+
+use core::error;
+use core::fmt;
+use implicant::{low_mask, Cover, Implicant};
+use prelude::*;
+
+/// A single input variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    /// Which bit of the input holds the variable.
+    pub bit: u32,
+    /// If set, the bit is 1 when the variable is asserted, but the signal
+    /// called `name` is low then, like `reset_n`.  Everything that names
+    /// the signal (display, exporters, parsers) inverts the literal.
+    pub active_low: bool,
+}
+
+impl Variable {
+    pub fn new(name: &str, bit: u32) -> Self {
+        Variable {
+            name: name.to_string(),
+            bit,
+            active_low: false,
+        }
+    }
+
+    pub fn active_low(name: &str, bit: u32) -> Self {
+        Variable {
+            active_low: true,
+            ..Variable::new(name, bit)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VariablesError {
+    /// Two variables have the same name.
+    DuplicateName(String),
+    /// Not exactly one variable per bit, up to the arity.
+    BadBit(u32),
+    /// Names must be non-empty and must not contain whitespace.
+    BadName(String),
+    /// The variables were used for a function of a different arity.
+    ArityMismatch { variables: u32, arity: u32 },
+}
+
+impl fmt::Display for VariablesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VariablesError::DuplicateName(ref name) => write!(f, "Duplicate variable '{}'", name),
+            VariablesError::BadBit(bit) => write!(f, "Bit {} is missing or used twice", bit),
+            VariablesError::BadName(ref name) => write!(f, "Bad variable name '{}'", name),
+            VariablesError::ArityMismatch { variables, arity } => {
+                write!(f, "Have {} variables, but the arity is {}", variables, arity)
+            }
+        }
+    }
+}
+
+impl error::Error for VariablesError {}
+
+/// The variables of a function, exactly one per bit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variables {
+    /// Sorted by bit, so `vars[i].bit == i`.
+    vars: Vec<Variable>,
+}
+
+impl Variables {
+    /// Checks that the bits are exactly `0..vars.len()`, in any order,
+    /// and that the names are unique.
+    pub fn new(mut vars: Vec<Variable>) -> Result<Self, VariablesError> {
+        if vars.len() > 32 {
+            return Err(VariablesError::BadBit(32));
+        }
+        vars.sort_by_key(|var| var.bit);
+        for (i, var) in vars.iter().enumerate() {
+            if var.bit != i as u32 {
+                return Err(VariablesError::BadBit(i as u32));
+            }
+            if var.name.is_empty() || var.name.contains(char::is_whitespace) {
+                return Err(VariablesError::BadName(var.name.clone()));
+            }
+            if vars[..i].iter().any(|other| other.name == var.name) {
+                return Err(VariablesError::DuplicateName(var.name.clone()));
+            }
+        }
+        Ok(Variables { vars })
+    }
+
+    /// Active-high variables, where `names[i]` is bit `i`.
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Result<Self, VariablesError> {
+        Variables::new(names.iter()
+                           .enumerate()
+                           .map(|(i, name)| Variable::new(name.as_ref(), i as u32))
+                           .collect())
+    }
+
+    pub fn arity(&self) -> u32 {
+        self.vars.len() as u32
+    }
+
+    /// Fails unless there is exactly one variable per bit of `arity`.
+    pub fn check_arity(&self, arity: u32) -> Result<(), VariablesError> {
+        if self.arity() != arity {
+            return Err(VariablesError::ArityMismatch {
+                variables: self.arity(),
+                arity,
+            });
+        }
+        Ok(())
+    }
+
+    /// In bit order.
+    pub fn iter(&self) -> ::core::slice::Iter<'_, Variable> {
+        self.vars.iter()
+    }
+
+    pub fn get(&self, bit: u32) -> &Variable {
+        &self.vars[bit as usize]
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Variable> {
+        self.vars.iter().find(|var| var.name == name)
+    }
+
+    /// The names in bit order, as the exporters take them.
+    pub fn names(&self) -> Vec<&str> {
+        self.vars.iter().map(|var| var.name.as_str()).collect()
+    }
+
+    /// Bits of the active-low variables.
+    pub fn active_low_mask(&self) -> u32 {
+        self.vars.iter().filter(|var| var.active_low).fold(0, |acc, var| acc | (1 << var.bit))
+    }
+
+    /// The same cube in terms of the named signals, i.e. with the literals
+    /// of active-low variables inverted.  Its own inverse.
+    pub fn to_signals(&self, imp: &Implicant) -> Implicant {
+        assert_eq!(0, imp.value & !low_mask(self.arity()),
+                   "Implicant {:?} uses more than {} variables", imp, self.arity());
+        Implicant::new(imp.mask_gap, imp.value ^ (self.active_low_mask() & !imp.mask_gap))
+    }
+
+    /// `to_signals` for every implicant.
+    pub fn cover_to_signals(&self, cover: &Cover) -> Cover {
+        assert_eq!(self.arity(), cover.arity, "Cover has the wrong arity");
+        Cover::new(cover.arity, cover.iter().map(|imp| self.to_signals(imp)).collect())
+    }
+}
+
+#[test]
+fn test_validation() {
+    let vars = Variables::new(vec![Variable::new("b", 1),
+                                   Variable::active_low("rst_n", 2),
+                                   Variable::new("a", 0)])
+        .unwrap();
+    assert_eq!(vec!["a", "b", "rst_n"], vars.names());
+    assert_eq!(Some(2), vars.find("rst_n").map(|var| var.bit));
+    assert_eq!(0b100, vars.active_low_mask());
+    assert_eq!(Ok(()), vars.check_arity(3));
+    assert_eq!(Err(VariablesError::ArityMismatch { variables: 3, arity: 4 }),
+               vars.check_arity(4));
+
+    assert_eq!(Err(VariablesError::BadBit(1)),
+               Variables::new(vec![Variable::new("a", 0), Variable::new("b", 2)]));
+    assert_eq!(Err(VariablesError::BadBit(1)),
+               Variables::new(vec![Variable::new("a", 0), Variable::new("b", 0)]));
+    assert_eq!(Err(VariablesError::DuplicateName("a".to_string())),
+               Variables::from_names(&["a", "b", "a"]));
+    assert_eq!(Err(VariablesError::BadName("a b".to_string())),
+               Variables::from_names(&["a b"]));
+}
+
+#[test]
+fn test_to_signals() {
+    let vars = Variables::new(vec![Variable::new("a", 0), Variable::active_low("rst_n", 1)]).unwrap();
+    // a & rst, i.e. a & !rst_n
+    let imp = Implicant::new(0, 0b11);
+    assert_eq!(Implicant::new(0, 0b01), vars.to_signals(&imp));
+    assert_eq!(imp, vars.to_signals(&vars.to_signals(&imp)));
+    // Gaps stay gaps.
+    assert_eq!(Implicant::new(0b10, 0b01), vars.to_signals(&Implicant::new(0b10, 0b01)));
+}
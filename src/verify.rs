@@ -0,0 +1,199 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checks that a cover implements a function, word-parallel over the
+//! truth table: Each cube is painted into a bitset 64 inputs at a time,
+//! and then compared against the sampled ON and don't-care sets.
+
+use esop::Esop;
+use implicant::{low_mask, Cover, Implicant};
+use masked_count;
+use prelude::*;
+
+/// The smallest input where cover and function disagree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The function is `1` here, but no cube contains the input.
+    Uncovered(u32),
+    /// The function is `0` here, but some cube contains the input.
+    Covered(u32),
+}
+
+/// One bit per input, like `bits::Bitset`, but exposing the words.
+fn table_of(arity: u32, f: &dyn Fn(u32) -> bool) -> Vec<u64> {
+    let mut words = vec![0u64; words_for(arity)];
+    for v in 0..(1u32 << arity) {
+        if f(v) {
+            words[(v >> 6) as usize] |= 1 << (v & 63);
+        }
+    }
+    words
+}
+
+fn words_for(arity: u32) -> usize {
+    (1usize << arity).div_ceil(64)
+}
+
+/// Sets the bits of all inputs inside `imp`.
+fn paint(words: &mut [u64], imp: &Implicant) {
+    for_each_word(imp, |word, pattern| words[word] |= pattern);
+}
+
+/// Flips the bits of all inputs inside `imp`.
+fn toggle(words: &mut [u64], imp: &Implicant) {
+    for_each_word(imp, |word, pattern| words[word] ^= pattern);
+}
+
+/// Calls `f` with every word that intersects `imp`, and the bits inside.
+fn for_each_word<F: FnMut(usize, u64)>(imp: &Implicant, mut f: F) {
+    // Which of the 64 inputs of a word lie inside, judging by the low six
+    // bits only.  The high bits then select the words.
+    let mut pattern = 0u64;
+    for j in 0..64u32 {
+        if (j & !imp.mask_gap & 63) == (imp.value & 63) {
+            pattern |= 1 << j;
+        }
+    }
+    for sub in masked_count::up(imp.mask_gap >> 6) {
+        f((sub | (imp.value >> 6)) as usize, pattern);
+    }
+}
+
+/// Like `verify_cover_with_dc`, without don't-cares.
+pub fn verify_cover(sampling_fn: &dyn Fn(u32) -> bool, arity: u32, cover: &Cover) -> Result<(), Mismatch> {
+    verify_cover_with_dc(sampling_fn, &|_| false, arity, cover)
+}
+
+/// Checks that `cover` contains every input where `on_fn` holds, and no
+/// input where it doesn't, ignoring inputs where `dc_fn` holds.
+/// Each function is called once per input.
+pub fn verify_cover_with_dc(on_fn: &dyn Fn(u32) -> bool,
+                            dc_fn: &dyn Fn(u32) -> bool,
+                            arity: u32,
+                            cover: &Cover)
+                            -> Result<(), Mismatch> {
+    assert!(arity < 32,
+            "Can only handle at most 31 bits, but tried {} bits",
+            arity);
+    assert_eq!(arity, cover.arity,
+               "Cover is for {} variables, but function has {}",
+               cover.arity, arity);
+    for imp in cover.iter() {
+        assert_eq!(0, (imp.mask_gap | imp.value) & !low_mask(arity),
+                   "Implicant {:?} uses more than {} variables", imp, arity);
+    }
+
+    let on = table_of(arity, on_fn);
+    let dc = table_of(arity, dc_fn);
+    let mut covered = vec![0u64; words_for(arity)];
+    for imp in cover.iter() {
+        paint(&mut covered, imp);
+    }
+    compare(arity, &on, &dc, covered)
+}
+
+/// Like `verify_cover`, but for an exclusive-or sum of products: inside
+/// means inside an odd number of cubes.  So `Uncovered` reports an input
+/// inside an even number of cubes, and `Covered` one inside an odd number.
+pub fn verify_esop(sampling_fn: &dyn Fn(u32) -> bool, arity: u32, esop: &Esop) -> Result<(), Mismatch> {
+    assert!(arity < 32,
+            "Can only handle at most 31 bits, but tried {} bits",
+            arity);
+    assert_eq!(arity, esop.arity,
+               "ESOP is for {} variables, but function has {}",
+               esop.arity, arity);
+    for imp in esop.iter() {
+        assert_eq!(0, (imp.mask_gap | imp.value) & !low_mask(arity),
+                   "Implicant {:?} uses more than {} variables", imp, arity);
+    }
+
+    let on = table_of(arity, sampling_fn);
+    let dc = vec![0u64; words_for(arity)];
+    let mut covered = vec![0u64; words_for(arity)];
+    for imp in esop.iter() {
+        toggle(&mut covered, imp);
+    }
+    compare(arity, &on, &dc, covered)
+}
+
+/// The smallest input where `on` and `covered` differ outside of `dc`.
+fn compare(arity: u32, on: &[u64], dc: &[u64], mut covered: Vec<u64>) -> Result<(), Mismatch> {
+    if arity < 6 {
+        // Points beyond the arity don't exist.
+        covered[0] &= (1u64 << (1 << arity)) - 1;
+    }
+
+    for (i, ((&on, &dc), &covered)) in on.iter().zip(dc).zip(&covered).enumerate() {
+        let wrong = (on ^ covered) & !dc;
+        if wrong != 0 {
+            let bit = wrong.trailing_zeros();
+            let point = ((i as u32) << 6) | bit;
+            return Err(if on & (1 << bit) != 0 {
+                Mismatch::Uncovered(point)
+            } else {
+                Mismatch::Covered(point)
+            });
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_verify_small() {
+    // f = x0 x1' + x1 x2
+    let f = |v: u32| ((v & 0b011) == 0b001) || ((v & 0b110) == 0b110);
+    let mut cover = Cover::new(3, vec![Implicant::new(0b001, 0b110), Implicant::new(0b100, 0b001)]);
+    assert_eq!(Ok(()), verify_cover(&f, 3, &cover));
+    cover.implicants.push(Implicant::new(0b011, 0b000));
+    assert_eq!(Err(Mismatch::Covered(0b000)), verify_cover(&f, 3, &cover));
+    assert_eq!(Ok(()), verify_cover_with_dc(&f, &|v| v & 0b100 == 0 && v != 0b001, 3, &cover));
+    cover.implicants.remove(0);
+    assert_eq!(Err(Mismatch::Uncovered(0b110)),
+               verify_cover_with_dc(&f, &|v| v & 0b100 == 0, 3, &cover));
+    assert_eq!(Ok(()), verify_cover(&|_| false, 0, &Cover::new(0, vec![])));
+    assert_eq!(Err(Mismatch::Uncovered(0)), verify_cover(&|_| true, 0, &Cover::new(0, vec![])));
+}
+
+#[test]
+fn test_verify_against_naive() {
+    // Crosses several words, and tests cubes with gaps on both sides.
+    let f = |v: u32| v.wrapping_mul(0x9E37_79B9) >> 29 < 3;
+    let cover = Cover::new(9, vec![Implicant::new(0b1_1000_0110, 0b0_0010_1000),
+                                   Implicant::new(0b0_0111_1111, 0b1_1000_0000),
+                                   Implicant::new(0b1_0101_0101, 0b0_0000_0000)]);
+    let naive = (0..512u32).find(|&v| f(v) != cover.contains(v)).unwrap();
+    let expected = if f(naive) {
+        Mismatch::Uncovered(naive)
+    } else {
+        Mismatch::Covered(naive)
+    };
+    assert_eq!(Err(expected), verify_cover(&f, 9, &cover));
+    assert_eq!(Ok(()), verify_cover(&|v| cover.contains(v), 9, &cover));
+}
+
+#[test]
+fn test_verify_esop() {
+    // a ⊕ b, where the overlap of the two cubes cancels
+    let f = |v: u32| (v ^ (v >> 1)) & 1 == 1;
+    let mut esop = Esop::new(2, vec![Implicant::new(0b10, 0b01), Implicant::new(0b01, 0b10)]);
+    assert_eq!(Ok(()), verify_esop(&f, 2, &esop));
+    esop.implicants.push(Implicant::new(0, 0b11));
+    assert_eq!(Err(Mismatch::Covered(0b11)), verify_esop(&f, 2, &esop));
+    esop.implicants.push(Implicant::new(0b11, 0));
+    assert_eq!(Err(Mismatch::Covered(0b00)), verify_esop(&f, 2, &esop));
+    esop.implicants.truncate(1);
+    assert_eq!(Err(Mismatch::Uncovered(0b10)), verify_esop(&f, 2, &esop));
+}
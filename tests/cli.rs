@@ -0,0 +1,57 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::process::Command;
+
+fn run(args: &[&str]) -> (i32, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_implicants"))
+        .args(args)
+        .output()
+        .expect("Cannot run binary");
+    (output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn main() {
+    // Mux: f = c ? b : a, with inputs (a, b, c) at bits 0, 1, 2
+    assert_eq!((0, "-11\n1-0\n11-\n".to_string()),
+               run(&["primes", "--hex", "ca"]));
+    assert_eq!((0, "-11\n1-0\n".to_string()),
+               run(&["minimize", "--minterms", "1,3,6,7", "--arity", "3"]));
+    assert_eq!((0, "-11\n1-0\n".to_string()),
+               run(&["minimize", "--expr", "a & b | a & !c | b & c"]));
+    assert_eq!((0, "-11 prime\n1-0 prime\n11- prime\n".to_string()),
+               run(&["all", "--expr", "a & b | a & !c | b & c", "--order", "size", "--max-literals", "2"]));
+
+    let (code, json) = run(&["all", "--hex", "8", "--format", "json", "--names", "x,y"]);
+    assert_eq!(0, code);
+    assert!(json.contains("\"names\": [\"x\", \"y\"]"));
+    assert!(json.contains("{\"cube\": \"11\", \"mask_gap\": 0, \"value\": 3, \"prime\": true}"));
+
+    let (code, pla) = run(&["minimize", "--hex", "e", "--format", "pla"]);
+    assert_eq!(0, code);
+    assert_eq!(".i 2\n.o 1\n.ob f\n.type f\n.p 2\n-1 1\n1- 1\n.e\n", pla);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(2, run(&[]).0);
+    assert_eq!(2, run(&["--hex", "123"]).0);
+    assert_eq!(2, run(&["--hex", "8", "--expr", "a"]).0);
+    assert_eq!(2, run(&["--minterms", "1,9", "--arity", "3"]).0);
+    assert_eq!(2, run(&["--expr", "a &"]).0);
+    assert_eq!(0, run(&["--help"]).0);
+}
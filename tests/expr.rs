@@ -14,24 +14,28 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-// extern crate libc;
+extern crate implicants;
 
-extern crate libc;
-use self::libc::{c_int, c_void, uint32_t};
+use implicants::Implicant;
+use implicants::emit::{emit, Language, Options};
+use implicants::expr::Expression;
 
-type SampleFnC = extern "C" fn(*mut c_void, uint32_t) -> c_int;
-type ReportFnC = extern "C" fn(*mut c_void, uint32_t, uint32_t, c_int);
+#[test]
+fn main() {
+    let expr = Expression::parse("sel -> hi & !lo").unwrap();
+    let mut primes = Vec::new();
+    {
+        let mut store_it = |mask_gap: u32, value: u32, is_prime: bool| {
+            if is_prime {
+                primes.push(Implicant::new(mask_gap, value));
+            }
+        };
+        expr.generate(&mut store_it);
+    }
+    primes.sort();
 
-#[no_mangle]
-pub extern "C" fn implicants_generate(sample: SampleFnC,
-                                      sample_base: *mut c_void,
-                                      report: ReportFnC,
-                                      report_base: *mut c_void,
-                                      arity: uint32_t) {
-    let sample_wrapped = &|v| sample(sample_base, v) != 0;
-    let report_wrapped = &mut |m, nonm, prime| {
-        report(report_base, m, nonm, if prime {1} else {0});
-    };
-
-    ::generate(sample_wrapped, report_wrapped, arity);
+    let names: Vec<&str> = expr.names().iter().map(|s| s.as_str()).collect();
+    let text = emit(&primes, &names, Language::Text, &Options::default());
+    println!("{}", text);
+    assert_eq!("hi·lo' + sel'", text);
 }
@@ -0,0 +1,59 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+extern crate implicants;
+
+use implicants::minimize::{minimize_with_dc, CostModel};
+use implicants::pla::Pla;
+
+#[test]
+fn main() {
+    let input = Pla::parse("\
+.i 3
+.o 2
+.ob f g
+.type fd
+000 10
+001 10
+010 1-
+011 01
+1-1 -1
+.e
+").unwrap();
+
+    let mut covers = Vec::new();
+    for o in 0..input.outputs {
+        covers.push(minimize_with_dc(&|v| input.value(o, v) == Some(true),
+                                     &|v| input.value(o, v).is_none(),
+                                     input.inputs,
+                                     &CostModel::terms(input.inputs)));
+    }
+    // Both need two cubes, but there are several equally cheap choices.
+    assert_eq!(2, covers[0].len());
+    assert_eq!(2, covers[1].len());
+
+    let mut output = Pla::from_covers(input.inputs, &[&covers[0], &covers[1]]);
+    output.output_labels = input.output_labels.clone();
+    println!("{}", output);
+    let reread = Pla::parse(&output.to_string()).unwrap();
+    for o in 0..input.outputs {
+        for v in 0..8 {
+            if let Some(expected) = input.value(o, v) {
+                assert_eq!(Some(expected), reread.value(o, v));
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// implicants – Enumerate (prime) implicants of an arbitrary function
+// Copyright (C) 2017  Ben Wiederhake
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "serde")]
+
+extern crate bincode;
+extern crate implicants;
+extern crate serde;
+extern crate serde_json;
+
+use implicants::minimize::{minimize, CostModel};
+use implicants::{Cover, Implicant, Statistics};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Cached {
+    #[serde(with = "implicants::serde_cubes")]
+    cover: Cover,
+    stats: Statistics,
+}
+
+#[test]
+fn main() {
+    let sample = |x: u32| (x & 0b110) != 0 && x != 0b111;
+    let stats = implicants::try_generate::<()>(&|x| Ok(sample(x)), &mut |_, _, _| {}, 3).unwrap();
+    let cached = Cached {
+        cover: minimize(&sample, 3, &CostModel::uniform(3, 1, 1)),
+        stats,
+    };
+
+    let json = serde_json::to_string(&cached).unwrap();
+    assert!(json.starts_with(r#"{"cover":{"arity":3,"cubes":["#), "{}", json);
+    assert_eq!(cached, serde_json::from_str(&json).unwrap());
+
+    let binary = bincode::serialize(&cached).unwrap();
+    assert_eq!(cached, bincode::deserialize(&binary).unwrap());
+
+    // The derived representation uses the masks:
+    let imp = Implicant::new(0b010, 0b100);
+    let json = serde_json::to_string(&imp).unwrap();
+    assert_eq!(r#"{"mask_gap":2,"value":4}"#, json);
+    assert_eq!(imp, serde_json::from_str(&json).unwrap());
+    let cover = Cover::new(3, vec![imp]);
+    assert_eq!(cover, serde_json::from_str(&serde_json::to_string(&cover).unwrap()).unwrap());
+}
+
+#[test]
+fn bad_cubes() {
+    let stats = r#""stats":{"implicants":0,"primes":0,"peak_bytes":0}"#;
+    let wrong_len = format!(r#"{{"cover":{{"arity":3,"cubes":["1-"]}},{}}}"#, stats);
+    let err = serde_json::from_str::<Cached>(&wrong_len).unwrap_err();
+    assert!(err.to_string().contains("Cube '1-' should have 3 characters"), "{}", err);
+    let wrong_char = format!(r#"{{"cover":{{"arity":3,"cubes":["1x0"]}},{}}}"#, stats);
+    let err = serde_json::from_str::<Cached>(&wrong_char).unwrap_err();
+    assert!(err.to_string().contains("Invalid cube '1x0'"), "{}", err);
+}
+
+#[test]
+fn bad_masks() {
+    let err = serde_json::from_str::<Implicant>(r#"{"mask_gap":1,"value":1}"#).unwrap_err();
+    assert!(err.to_string().contains("Gaps 1 and value 1 overlap"), "{}", err);
+    let err = serde_json::from_str::<Cover>(r#"{"arity":3,"implicants":[{"mask_gap":6,"value":4}]}"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("Gaps 110 and value 100 overlap"), "{}", err);
+}